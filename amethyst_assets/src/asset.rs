@@ -29,6 +29,18 @@ pub trait Asset: Send + Sync + 'static {
 
     /// The ECS storage type to be used. You'll want to use `DenseVecStorage` in most cases.
     type HandleStorage: UnprotectedStorage<Handle<Self>> + Send + Sync;
+
+    /// Returns the approximate amount of memory in bytes occupied by this asset.
+    ///
+    /// This is used to keep an `AssetStorage` within its memory budget, see
+    /// `AssetStorage::set_memory_budget`. The default implementation only accounts for
+    /// the size of `Self`, so assets owning heap allocations should override it.
+    fn memory_cost(&self) -> usize
+    where
+        Self: Sized,
+    {
+        std::mem::size_of::<Self>()
+    }
 }
 
 /// Defines a way to process asset's data into the asset. This allows
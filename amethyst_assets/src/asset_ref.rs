@@ -0,0 +1,223 @@
+use derivative::Derivative;
+use serde::{Deserialize, Serialize};
+
+use crate::{Asset, AssetStorage, Format, Handle, Loader, Progress, SerializableFormat};
+
+/// Reference to an asset by the source, path and format it's loaded from.
+///
+/// Unlike a `Handle`, this can be serialized, so save games and network messages can refer to
+/// assets. It's `Clone`, `Serialize` and `Deserialize`, so components containing it can be
+/// saved with `specs::saveload`.
+///
+/// A reference is created from a handle returned by the `Loader` with `from_handle`, and turned
+/// back into a handle with `load` after deserializing it, which reuses the asset if it's
+/// still loaded.
+///
+/// ```rust,ignore
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Weapon {
+///     model: AssetRef<Mesh, ObjFormat>,
+/// }
+///
+/// let model = AssetRef::from_handle(&mesh_handle, &mesh_storage).unwrap();
+/// // ... after deserializing
+/// let mesh_handle = weapon.model.load(&loader, (), &mesh_storage);
+/// ```
+///
+/// ### Type parameters:
+///
+/// - `A`: `Asset`,
+/// - `F`: `Format` for loading `A`
+#[derive(Derivative, Deserialize, Serialize)]
+#[derivative(Clone(bound = "F: Clone"), Debug(bound = "F: std::fmt::Debug"))]
+#[serde(bound(serialize = "F: Serialize", deserialize = "F: Deserialize<'de>"))]
+pub struct AssetRef<A, F = Box<dyn SerializableFormat<<A as Asset>::Data>>>
+where
+    A: Asset,
+    F: Format<A::Data>,
+{
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    source: String,
+    path: String,
+    format: F,
+    #[serde(skip)]
+    handle: Option<Handle<A>>,
+}
+
+impl<A, F> AssetRef<A, F>
+where
+    A: Asset,
+    F: Format<A::Data>,
+{
+    /// Creates a reference to the asset at `path` in the default source.
+    pub fn new<P: Into<String>>(path: P, format: F) -> Self {
+        AssetRef {
+            source: String::new(),
+            path: path.into(),
+            format,
+            handle: None,
+        }
+    }
+
+    /// Refers to the asset at the path in the source with id `source` instead.
+    pub fn with_source<S: Into<String>>(mut self, source: S) -> Self {
+        self.source = source.into();
+        self.handle = None;
+        self
+    }
+
+    /// Creates a reference to the asset behind `handle` from the source, path and format the
+    /// `Loader` loaded it with.
+    ///
+    /// With the default `F`, assets loaded with any format registered with `register_format` can
+    /// be referred to. Returns `None` if the asset wasn't loaded by the `Loader` (e.g. it was
+    /// loaded from data), or if it was loaded with another format type than `F`.
+    pub fn from_handle(handle: &Handle<A>, storage: &AssetStorage<A>) -> Option<Self> {
+        storage
+            .format_origin(handle)
+            .map(|(source, path, format)| AssetRef {
+                source,
+                path,
+                format,
+                handle: Some(handle.clone()),
+            })
+    }
+
+    /// Returns the id of the source the asset is loaded from, empty for the default source.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the path the asset is loaded from.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the handle to the asset, if it was created from one or loaded already.
+    pub fn handle(&self) -> Option<&Handle<A>> {
+        self.handle.as_ref()
+    }
+
+    /// Returns a handle to the asset, loading it if it isn't loaded yet.
+    ///
+    /// If the storage has a live handle to the asset loaded from the same source and path,
+    /// that one is returned instead of loading the asset again. The handle is kept, so later
+    /// calls return it right away.
+    pub fn load<P>(&mut self, loader: &Loader, progress: P, storage: &AssetStorage<A>) -> Handle<A>
+    where
+        P: Progress,
+    {
+        if let Some(ref handle) = self.handle {
+            return handle.clone();
+        }
+        let handle = match storage.loaded_from(&self.source, &self.path) {
+            Some(handle) => handle,
+            None => loader.load_from(
+                self.path.clone(),
+                *objekt::clone_box(&self.format),
+                self.source.as_str(),
+                progress,
+                storage,
+            ),
+        };
+        self.handle = Some(handle.clone());
+        handle
+    }
+}
+
+impl<A, F> PartialEq for AssetRef<A, F>
+where
+    A: Asset,
+    F: Format<A::Data>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source && self.path == other.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rayon::ThreadPoolBuilder;
+
+    use amethyst_core::ecs::VecStorage;
+
+    use amethyst_error::Error;
+
+    use super::*;
+    use crate as amethyst_assets;
+    use crate::{register_format, register_format_type, test_util::Level, Directory, RonFormat};
+
+    struct Map;
+
+    impl Asset for Map {
+        const NAME: &'static str = "Map";
+        type Data = MapData;
+        type HandleStorage = VecStorage<Handle<Self>>;
+    }
+
+    #[derive(Debug)]
+    struct MapData;
+    register_format_type!(MapData);
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    struct MapFormat;
+    register_format!("MAP", MapFormat as MapData);
+
+    impl Format<MapData> for MapFormat {
+        fn name(&self) -> &'static str {
+            "MAP"
+        }
+
+        fn import_simple(&self, _: Vec<u8>) -> Result<MapData, Error> {
+            Ok(MapData)
+        }
+    }
+
+    #[test]
+    fn asset_refs_serialize_to_their_path() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(Directory::new("assets"), pool);
+        let storage = AssetStorage::<Level>::new();
+        let handle = loader.load("levels/intro.ron", RonFormat, (), &storage);
+
+        let asset_ref = AssetRef::<Level, RonFormat>::from_handle(&handle, &storage).unwrap();
+        let serialized = ron::ser::to_string(&asset_ref).unwrap();
+        assert!(serialized.contains("levels/intro.ron"));
+
+        let mut deserialized: AssetRef<Level, RonFormat> = ron::de::from_str(&serialized).unwrap();
+        assert_eq!(asset_ref, deserialized);
+        assert_eq!(None, deserialized.handle());
+        assert_eq!(handle, deserialized.load(&loader, (), &storage));
+    }
+
+    #[test]
+    fn loaded_asset_refs_can_be_referred_to_again() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(Directory::new("assets"), pool);
+        let storage = AssetStorage::<Level>::new();
+
+        let mut asset_ref = AssetRef::<Level, RonFormat>::new("levels/intro.ron", RonFormat);
+        let handle = asset_ref.load(&loader, (), &storage);
+
+        let saved_again = AssetRef::<Level, RonFormat>::from_handle(&handle, &storage).unwrap();
+        assert_eq!(asset_ref, saved_again);
+    }
+
+    #[test]
+    fn assets_loaded_with_registered_formats_can_be_referred_to_by_boxed_format() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(Directory::new("assets"), pool);
+        let storage = AssetStorage::<Map>::new();
+        let handle = loader.load("maps/town.map", MapFormat, (), &storage);
+
+        let asset_ref = AssetRef::<Map>::from_handle(&handle, &storage).unwrap();
+        assert_eq!("maps/town.map", asset_ref.path());
+        let serialized = ron::ser::to_string(&asset_ref).unwrap();
+        assert!(serialized.contains("MAP"));
+
+        let mut deserialized: AssetRef<Map> = ron::de::from_str(&serialized).unwrap();
+        assert_eq!(handle, deserialized.load(&loader, (), &storage));
+    }
+}
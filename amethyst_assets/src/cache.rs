@@ -7,16 +7,16 @@ use crate::{Handle, WeakHandle};
 
 /// A simple cache for asset handles of type `A`.
 /// This stores `WeakHandle`, so it doesn't keep the assets alive.
+///
+/// To cache assets loaded by logical id, key it with `Loader::canonical_id` or use
+/// `AssetLoaderSystemData::load_cached`.
 #[derive(Derivative)]
 #[derivative(Default(bound = ""))]
 pub struct Cache<A> {
     map: FnvHashMap<String, WeakHandle<A>>,
 }
 
-impl<A> Cache<A>
-where
-    A: Clone,
-{
+impl<A> Cache<A> {
     /// Creates a new `Cache` and initializes it with the default values.
     pub fn new() -> Self {
         Default::default()
@@ -0,0 +1,286 @@
+//! Transparent decompression of assets.
+
+use std::sync::Arc;
+
+use amethyst_error::{format_err, Error, ResultExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{Format, FormatValue, Source};
+
+/// A compression algorithm supported by `Compressed`.
+///
+/// Every algorithm is behind the feature of the same name, decompressing data
+/// with a disabled algorithm fails.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum Compression {
+    /// Gzip, file extension `.gz`.
+    Gzip,
+    /// Zstandard, file extension `.zst`.
+    Zstd,
+    /// LZ4 frame format, file extension `.lz4`.
+    Lz4,
+}
+
+impl Compression {
+    /// Returns the compression indicated by the extension of `path`, e.g. `Zstd` for
+    /// `"level.ron.zst"`.
+    pub fn from_path(path: &str) -> Option<Compression> {
+        match path.rsplit('.').next() {
+            Some("gz") => Some(Compression::Gzip),
+            Some("zst") => Some(Compression::Zstd),
+            Some("lz4") => Some(Compression::Lz4),
+            _ => None,
+        }
+    }
+
+    /// Returns the file extension of this compression, without the leading dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Zstd => "zst",
+            Compression::Lz4 => "lz4",
+        }
+    }
+
+    /// Decompresses `bytes`.
+    pub fn decompress(self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                use std::io::Read;
+
+                let mut out = Vec::new();
+                flate2::read::GzDecoder::new(bytes)
+                    .read_to_end(&mut out)
+                    .with_context(|_| format_err!("Failed decompressing gzip data"))?;
+                Ok(out)
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::stream::decode_all(bytes)
+                .with_context(|_| format_err!("Failed decompressing zstd data")),
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => {
+                use std::io::Read;
+
+                let mut out = Vec::new();
+                lz4::Decoder::new(bytes)
+                    .and_then(|mut decoder| decoder.read_to_end(&mut out))
+                    .with_context(|_| format_err!("Failed decompressing lz4 data"))?;
+                Ok(out)
+            }
+            #[allow(unreachable_patterns)]
+            other => Err(format_err!(
+                "Decompressing {:?} data requires the `{}` feature of amethyst_assets",
+                other,
+                other.feature(),
+            )),
+        }
+    }
+
+    /// Compresses `bytes`, e.g. for writing cooked assets.
+    pub fn compress(self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                use std::io::Write;
+
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(bytes)
+                    .and_then(|_| encoder.finish())
+                    .with_context(|_| format_err!("Failed compressing gzip data"))
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::stream::encode_all(bytes, 0)
+                .with_context(|_| format_err!("Failed compressing zstd data")),
+            #[cfg(feature = "lz4")]
+            Compression::Lz4 => {
+                use std::io::Write;
+
+                let mut encoder = lz4::EncoderBuilder::new()
+                    .build(Vec::new())
+                    .with_context(|_| format_err!("Failed compressing lz4 data"))?;
+                encoder
+                    .write_all(bytes)
+                    .with_context(|_| format_err!("Failed compressing lz4 data"))?;
+                let (out, result) = encoder.finish();
+                result.with_context(|_| format_err!("Failed compressing lz4 data"))?;
+                Ok(out)
+            }
+            #[allow(unreachable_patterns)]
+            other => Err(format_err!(
+                "Compressing {:?} data requires the `{}` feature of amethyst_assets",
+                other,
+                other.feature(),
+            )),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn feature(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Lz4 => "lz4",
+        }
+    }
+}
+
+/// Format adapter decompressing the bytes of an asset before passing them to
+/// the inner format.
+///
+/// The compression is selected by the file extension of the asset, unless
+/// it's specified explicitly with `with_compression`.
+///
+/// ```rust,ignore
+/// loader.load("level.ron.zst", Compressed::new(RonFormat), (), &storage);
+/// loader.load("sprite.bin", Compressed::with_compression(ImageFormat::default(), Compression::Lz4), (), &storage);
+/// ```
+///
+/// Other files loaded by the inner format (like buffers of a glTF file) are
+/// decompressed if their extension names a compression, too.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Compressed<F> {
+    format: F,
+    compression: Option<Compression>,
+}
+
+impl<F> Compressed<F> {
+    /// Wraps `format`, detecting the compression by file extension.
+    pub fn new(format: F) -> Self {
+        Compressed {
+            format,
+            compression: None,
+        }
+    }
+
+    /// Wraps `format`, always decompressing the asset with `compression`.
+    pub fn with_compression(format: F, compression: Compression) -> Self {
+        Compressed {
+            format,
+            compression: Some(compression),
+        }
+    }
+
+    /// Returns the inner format.
+    pub fn inner(&self) -> &F {
+        &self.format
+    }
+}
+
+impl<D, F> Format<D> for Compressed<F>
+where
+    D: 'static,
+    F: Format<D> + Clone,
+{
+    fn name(&self) -> &'static str {
+        self.format.name()
+    }
+
+    fn import(
+        &self,
+        name: String,
+        source: Arc<dyn Source>,
+        create_reload: Option<Box<dyn Format<D>>>,
+    ) -> Result<FormatValue<D>, Error> {
+        let source = Arc::new(DecompressingSource {
+            compression: self.compression,
+            name: name.clone(),
+            source,
+        });
+        // Reloading goes through the decompressing source already.
+        let create_reload =
+            create_reload.map(|_| Box::new(self.format.clone()) as Box<dyn Format<D>>);
+
+        self.format.import(name, source, create_reload)
+    }
+}
+
+/// Source decompressing the bytes loaded from the wrapped source.
+struct DecompressingSource {
+    compression: Option<Compression>,
+    name: String,
+    source: Arc<dyn Source>,
+}
+
+impl DecompressingSource {
+    fn decompress(&self, path: &str, bytes: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let compression = match self.compression {
+            Some(compression) if path == self.name => Some(compression),
+            _ => Compression::from_path(path),
+        };
+
+        match compression {
+            Some(compression) => compression
+                .decompress(&bytes)
+                .with_context(|_| format_err!("Failed decompressing {:?}", path)),
+            None => Ok(bytes),
+        }
+    }
+}
+
+impl Source for DecompressingSource {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        self.source.modified(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        let bytes = self.source.load(path)?;
+        self.decompress(path, bytes)
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), Error> {
+        let (bytes, modified) = self.source.load_with_metadata(path)?;
+        Ok((self.decompress(path, bytes)?, modified))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Compression;
+
+    #[test]
+    fn detects_compression_by_extension() {
+        assert_eq!(
+            Some(Compression::Zstd),
+            Compression::from_path("level.ron.zst")
+        );
+        assert_eq!(
+            Some(Compression::Gzip),
+            Compression::from_path("a/b.png.gz")
+        );
+        assert_eq!(Some(Compression::Lz4), Compression::from_path("mesh.lz4"));
+        assert_eq!(None, Compression::from_path("level.ron"));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn compressed_format_decompresses_asset() {
+        use std::sync::Arc;
+
+        use amethyst_error::Error;
+
+        use super::Compressed;
+        use crate::{Format, Source};
+
+        struct Single(Vec<u8>);
+
+        impl Source for Single {
+            fn modified(&self, _: &str) -> Result<u64, Error> {
+                Ok(0)
+            }
+
+            fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+                Ok(self.0.clone())
+            }
+        }
+
+        let bytes = Compression::Gzip.compress(b"\"compressed\"").unwrap();
+        let format = Compressed::new(crate::RonFormat);
+        let value =
+            Format::<String>::import(&format, "text.ron.gz".into(), Arc::new(Single(bytes)), None)
+                .unwrap();
+        assert_eq!("compressed", value.data);
+    }
+}
@@ -0,0 +1,415 @@
+//! Caching imported asset data on disk, see `ImportCache`.
+
+use std::{
+    any::type_name,
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use amethyst_error::{format_err, Error, ResultExt};
+use fnv::FnvHasher;
+use log::{debug, warn};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{Directory, Format, FormatValue, Reload, SingleFile, Source};
+
+/// Version of the layout of cooked files, part of every cache key.
+const COOK_VERSION: u32 = 1;
+
+/// A directory storing imported asset data, keyed by a hash of the asset's bytes,
+/// its format (including the format options) and its data type.
+///
+/// Assets are read from the cache by loading them through `Loader::load_cooked`
+/// or the `Cooked` format adapter, which import and store assets that aren't
+/// cooked yet. A `Cooker` cooks a whole asset directory ahead of time.
+///
+/// Only the bytes of the asset file itself are hashed, so assets consisting of
+/// several files are only cooked again when their main file changes.
+#[derive(Clone, Debug)]
+pub struct ImportCache {
+    dir: PathBuf,
+}
+
+impl ImportCache {
+    /// Creates a cache storing cooked assets in `dir`.
+    /// The directory is created once the first asset is stored.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        ImportCache { dir: dir.into() }
+    }
+
+    /// Returns the directory of this cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Computes the cache key of the asset data imported from `bytes` with `format`.
+    pub fn key<D, F>(format: &F, bytes: &[u8]) -> u64
+    where
+        D: 'static,
+        F: Format<D>,
+    {
+        let mut hasher = FnvHasher::default();
+        COOK_VERSION.hash(&mut hasher);
+        type_name::<D>().hash(&mut hasher);
+        format.name().hash(&mut hasher);
+        format!("{:?}", format).hash(&mut hasher);
+        bytes.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// Returns `true` if data with the given key has been cooked.
+    pub fn contains(&self, key: u64) -> bool {
+        self.path(key).is_file()
+    }
+
+    /// Reads the cooked data with the given key, returning `None` if it hasn't been cooked.
+    pub fn load<D>(&self, key: u64) -> Result<Option<D>, Error>
+    where
+        D: DeserializeOwned,
+    {
+        let path = self.path(key);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&path).with_context(|_| format_err!("Failed to read {:?}", path))?;
+        let data = bincode::deserialize(&bytes)
+            .with_context(|_| format_err!("Failed deserializing cooked asset {:?}", path))?;
+
+        Ok(Some(data))
+    }
+
+    /// Stores cooked data with the given key.
+    pub fn store<D>(&self, key: u64, data: &D) -> Result<(), Error>
+    where
+        D: Serialize,
+    {
+        let bytes = bincode::serialize(data)
+            .with_context(|_| format_err!("Failed serializing cooked asset"))?;
+        fs::create_dir_all(&self.dir)
+            .with_context(|_| format_err!("Failed to create directory {:?}", self.dir))?;
+
+        // Write to a temporary file first, so readers never see partially written data.
+        let path = self.path(key);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp, &bytes).with_context(|_| format_err!("Failed to write {:?}", tmp))?;
+        fs::rename(&tmp, &path).with_context(|_| format_err!("Failed to write {:?}", path))?;
+
+        Ok(())
+    }
+
+    /// Imports the asset `name` from `source` with `format` and stores the data,
+    /// unless it's cooked already.
+    ///
+    /// Returns `true` if the asset was imported.
+    pub fn cook<D, F>(&self, name: &str, format: &F, source: Arc<dyn Source>) -> Result<bool, Error>
+    where
+        D: Serialize + 'static,
+        F: Format<D>,
+    {
+        let bytes = source
+            .load(name)
+            .with_context(|_| crate::error::Error::Source)?;
+        let key = Self::key(format, &bytes);
+        if self.contains(key) {
+            return Ok(false);
+        }
+
+        let data = format
+            .import(name.into(), source, None)
+            .with_context(|_| crate::error::Error::Format(format.name()))?
+            .data;
+        self.store(key, &data)?;
+
+        Ok(true)
+    }
+
+    fn path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}.cooked", key))
+    }
+}
+
+/// Format adapter reading the asset data from an `ImportCache` if it has been cooked
+/// from the same bytes, and importing and cooking it with the inner format otherwise.
+///
+/// Usually created by `Loader::load_cooked`.
+#[derive(Clone, Debug)]
+pub struct Cooked<F> {
+    format: F,
+    cache: Arc<ImportCache>,
+}
+
+impl<F> Cooked<F> {
+    /// Wraps `format`, using `cache` for cooked data.
+    pub fn new(format: F, cache: Arc<ImportCache>) -> Self {
+        Cooked { format, cache }
+    }
+}
+
+impl<D, F> Format<D> for Cooked<F>
+where
+    D: Serialize + DeserializeOwned + Send + Sync + 'static,
+    F: Format<D> + Clone,
+{
+    fn name(&self) -> &'static str {
+        self.format.name()
+    }
+
+    fn import(
+        &self,
+        name: String,
+        source: Arc<dyn Source>,
+        create_reload: Option<Box<dyn Format<D>>>,
+    ) -> Result<FormatValue<D>, Error> {
+        let (bytes, modified) = source
+            .load_with_metadata(&name)
+            .with_context(|_| crate::error::Error::Source)?;
+        let key = ImportCache::key(&self.format, &bytes);
+
+        let cooked = self.cache.load(key).unwrap_or_else(|e| {
+            warn!("Failed to read cooked asset {:?}: {}", name, e);
+            None
+        });
+        let data = match cooked {
+            Some(data) => {
+                debug!("Using cooked data for asset {:?}", name);
+                data
+            }
+            None => {
+                let data = self.format.import(name.clone(), source.clone(), None)?.data;
+                if let Err(e) = self.cache.store(key, &data) {
+                    warn!("Failed to cook asset {:?}: {}", name, e);
+                }
+                data
+            }
+        };
+        let reload = create_reload.map(|format| {
+            Box::new(SingleFile::new(format, modified, name, source)) as Box<dyn Reload<D>>
+        });
+
+        Ok(FormatValue { data, reload })
+    }
+}
+
+/// Result of `Cooker::cook_directory`.
+#[derive(Debug, Default)]
+pub struct CookReport {
+    /// Assets which have been imported and stored.
+    pub cooked: Vec<String>,
+    /// Assets which were cooked already.
+    pub up_to_date: Vec<String>,
+    /// Files without a format registered for their extension.
+    pub skipped: Vec<String>,
+    /// Assets which failed to import, with the error.
+    pub failed: Vec<(String, Error)>,
+}
+
+type CookFn = Box<dyn Fn(&ImportCache, &str, Arc<dyn Source>) -> Result<bool, Error>>;
+
+/// Cooks all assets of a directory ahead of time, choosing the format by file extension.
+///
+/// ```rust,ignore
+/// let report = Cooker::new(ImportCache::new("cache"))
+///     .with_format("png", ImageFormat::default())
+///     .with_format::<Prefab<MyPrefabData>, _>("ron", RonFormat)
+///     .cook_directory("assets")?;
+/// ```
+///
+/// The formats have to be configured exactly like the ones used for loading,
+/// otherwise the cache keys don't match.
+pub struct Cooker {
+    cache: ImportCache,
+    formats: Vec<(String, CookFn)>,
+}
+
+impl Cooker {
+    /// Creates a cooker storing the cooked assets in `cache`.
+    pub fn new(cache: ImportCache) -> Self {
+        Cooker {
+            cache,
+            formats: Vec::new(),
+        }
+    }
+
+    /// Cooks files whose name ends with `.{extension}` into `D` with `format`.
+    ///
+    /// If several formats match a file, the one added first is used.
+    pub fn with_format<D, F>(mut self, extension: &str, format: F) -> Self
+    where
+        D: Serialize + 'static,
+        F: Format<D>,
+    {
+        self.formats.push((
+            format!(".{}", extension.to_lowercase()),
+            Box::new(move |cache, name, source| cache.cook::<D, F>(name, &format, source)),
+        ));
+        self
+    }
+
+    /// Cooks all files in `dir` and its subdirectories.
+    pub fn cook_directory<P>(&self, dir: P) -> Result<CookReport, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        let dir = dir.into();
+        let mut files = Vec::new();
+        collect_files(&dir, "", &mut files)?;
+        files.sort();
+
+        let source = Arc::new(Directory::new(dir)) as Arc<dyn Source>;
+        let mut report = CookReport::default();
+        for file in files {
+            let lowercase = file.to_lowercase();
+            let cook = match self
+                .formats
+                .iter()
+                .find(|(extension, _)| lowercase.ends_with(extension.as_str()))
+            {
+                Some((_, cook)) => cook,
+                None => {
+                    report.skipped.push(file);
+                    continue;
+                }
+            };
+
+            match cook(&self.cache, &file, source.clone()) {
+                Ok(true) => report.cooked.push(file),
+                Ok(false) => report.up_to_date.push(file),
+                Err(e) => report.failed.push((file, e)),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Collects the paths of all files in `dir` relative to the root, separated by `/`.
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<String>) -> Result<(), Error> {
+    let entries = fs::read_dir(dir).with_context(|_| format_err!("Failed to read {:?}", dir))?;
+    for entry in entries {
+        let entry = entry.with_context(|_| format_err!("Failed to read {:?}", dir))?;
+        let name = format!("{}{}", prefix, entry.file_name().to_string_lossy());
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, &format!("{}/", name), files)?;
+        } else {
+            files.push(name);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use amethyst_error::Error;
+    use derivative::Derivative;
+
+    use super::{Cooked, Cooker, ImportCache};
+    use crate::{Format, Source};
+
+    /// Format counting how often it actually imported an asset.
+    #[derive(Clone, Default, Derivative)]
+    #[derivative(Debug)]
+    struct CountingFormat {
+        // ignored so the count doesn't change the cache key
+        #[derivative(Debug = "ignore")]
+        imports: Arc<AtomicUsize>,
+    }
+
+    impl CountingFormat {
+        fn imports(&self) -> usize {
+            self.imports.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Format<String> for CountingFormat {
+        fn name(&self) -> &'static str {
+            "Counting"
+        }
+
+        fn import_simple(&self, bytes: Vec<u8>) -> Result<String, Error> {
+            self.imports.fetch_add(1, Ordering::SeqCst);
+            Ok(String::from_utf8(bytes)?)
+        }
+    }
+
+    struct Single(&'static str);
+
+    impl Source for Single {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            Ok(self.0.as_bytes().to_vec())
+        }
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("amethyst_cook_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn cooked_format_imports_only_changed_assets() {
+        let cache = Arc::new(ImportCache::new(temp_dir("format")));
+        let counting = CountingFormat::default();
+        let format = Cooked::new(counting.clone(), cache.clone());
+        let import = |contents| {
+            Format::<String>::import(&format, "asset".into(), Arc::new(Single(contents)), None)
+                .unwrap()
+                .data
+        };
+
+        assert_eq!("first", import("first"));
+        assert_eq!("first", import("first"));
+        assert_eq!(1, counting.imports());
+
+        assert_eq!("second", import("second"));
+        assert_eq!(2, counting.imports());
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn cooker_cooks_matching_files() {
+        let assets = temp_dir("assets");
+        fs::create_dir_all(assets.join("sub")).unwrap();
+        fs::write(assets.join("sub/a.txt"), "a").unwrap();
+        fs::write(assets.join("b.bin"), "b").unwrap();
+        let cache = ImportCache::new(temp_dir("cache"));
+
+        let counting = CountingFormat::default();
+        let cooker = Cooker::new(cache.clone()).with_format("txt", counting.clone());
+        let report = cooker.cook_directory(&assets).unwrap();
+        assert_eq!(vec!["sub/a.txt".to_owned()], report.cooked);
+        assert_eq!(vec!["b.bin".to_owned()], report.skipped);
+        assert_eq!(1, counting.imports());
+
+        let report = cooker.cook_directory(&assets).unwrap();
+        assert_eq!(vec!["sub/a.txt".to_owned()], report.up_to_date);
+        assert_eq!(1, counting.imports());
+        let key = ImportCache::key::<String, _>(&counting, b"a");
+        assert_eq!(Some("a".to_owned()), cache.load(key).unwrap());
+
+        fs::remove_dir_all(&assets).unwrap();
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+}
@@ -0,0 +1,259 @@
+//! Tracking of assets loaded on behalf of other assets.
+
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+thread_local! {
+    static COLLECTED: RefCell<Option<Vec<Dependency>>> = RefCell::new(None);
+}
+
+/// A dependency of one asset on another one, e.g. of a `Prefab` on the meshes it loads.
+///
+/// Created with `AssetStorage::dependency` and registered with `AssetStorage::add_dependency`.
+/// Assets loaded through the `Loader` while another asset is processed (like in
+/// `PrefabData::load_sub_assets`) are registered as dependencies automatically.
+///
+/// Whenever the dependency is hot-reloaded (or replaced), the dependent asset is reloaded
+/// and processed again, which in turn notifies its own dependents.
+#[derive(Clone, Debug)]
+pub struct Dependency {
+    asset_type_name: &'static str,
+    handle_id: u32,
+    reloads: Arc<AtomicUsize>,
+    seen: usize,
+    /// Whether the dependency was recorded automatically, rather than registered with
+    /// `AssetStorage::add_dependency`.
+    pub(crate) collected: bool,
+}
+
+impl Dependency {
+    pub(crate) fn new(
+        asset_type_name: &'static str,
+        handle_id: u32,
+        reloads: Arc<AtomicUsize>,
+    ) -> Self {
+        let seen = reloads.load(Ordering::Relaxed);
+        Dependency {
+            asset_type_name,
+            handle_id,
+            reloads,
+            seen,
+            collected: false,
+        }
+    }
+
+    /// Returns the name of the asset type of the dependency.
+    pub fn asset_type_name(&self) -> &'static str {
+        self.asset_type_name
+    }
+
+    /// Returns the handle id of the dependency.
+    pub fn handle_id(&self) -> u32 {
+        self.handle_id
+    }
+
+    /// Returns `true` if the dependency was dropped by its storage.
+    pub(crate) fn is_dead(&self) -> bool {
+        Arc::strong_count(&self.reloads) == 1
+    }
+
+    /// Returns `true` if the dependency was reloaded since the last call.
+    pub(crate) fn poll_changed(&mut self) -> bool {
+        let reloads = self.reloads.load(Ordering::Relaxed);
+        let changed = reloads != self.seen;
+        self.seen = reloads;
+        changed
+    }
+}
+
+/// Runs `f`, collecting all dependencies recorded on this thread in the meantime.
+pub(crate) fn collect<F, R>(f: F) -> (R, Vec<Dependency>)
+where
+    F: FnOnce() -> R,
+{
+    let outer = COLLECTED.with(|c| c.replace(Some(Vec::new())));
+    let ret = f();
+    let collected = COLLECTED.with(|c| c.replace(outer));
+
+    (ret, collected.unwrap_or_default())
+}
+
+/// Records a dependency if called from within `collect`.
+pub(crate) fn record<F>(dependency: F)
+where
+    F: FnOnce() -> Dependency,
+{
+    COLLECTED.with(|c| {
+        if let Some(ref mut collected) = *c.borrow_mut() {
+            let mut dependency = dependency();
+            dependency.collected = true;
+            collected.push(dependency);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rayon::ThreadPoolBuilder;
+
+    use amethyst_core::ecs::VecStorage;
+    use amethyst_error::Error;
+
+    use super::Dependency;
+    use crate::{
+        storage::Processed, test_util::run_until, Asset, AssetStorage, FormatValue, Handle, Loader,
+        ProcessableAsset, ProcessingState, Reload,
+    };
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Leaf(u32);
+
+    impl Asset for Leaf {
+        const NAME: &'static str = "Leaf";
+        type Data = Self;
+        type HandleStorage = VecStorage<Handle<Self>>;
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Dependent(u32);
+
+    impl Asset for Dependent {
+        const NAME: &'static str = "Dependent";
+        type Data = Self;
+        type HandleStorage = VecStorage<Handle<Self>>;
+    }
+
+    #[derive(Clone)]
+    struct ReloadTo<D>(D);
+
+    impl<D: Clone + Send + Sync + 'static> Reload<D> for ReloadTo<D> {
+        fn needs_reload(&self) -> bool {
+            false
+        }
+
+        fn name(&self) -> String {
+            "reload_to".into()
+        }
+
+        fn format(&self) -> &'static str {
+            "Test"
+        }
+
+        fn reload(self: Box<Self>) -> Result<FormatValue<D>, Error> {
+            Ok(FormatValue {
+                data: self.0.clone(),
+                reload: Some(self),
+            })
+        }
+    }
+
+    fn push_new<A: Asset>(
+        storage: &AssetStorage<A>,
+        data: A::Data,
+        reload: ReloadTo<A::Data>,
+    ) -> Handle<A>
+    where
+        A::Data: Clone,
+    {
+        let handle = storage.allocate();
+        storage.processed.push(Processed::NewAsset {
+            data: Ok(FormatValue {
+                data,
+                reload: Some(Box::new(reload)),
+            }),
+            handle: handle.clone(),
+            name: "test".into(),
+            tracker: Box::new(()),
+        });
+        handle
+    }
+
+    #[test]
+    fn assets_loaded_during_processing_are_recorded_as_dependencies() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::new(".", pool.clone());
+        let leaf_storage = AssetStorage::<Leaf>::new();
+        let mut dependent_storage = AssetStorage::<Dependent>::new();
+
+        let handle = loader.load_from_data(Dependent(0), (), &dependent_storage);
+        let mut leaf = None;
+        dependent_storage.process(
+            |d| {
+                leaf = Some(loader.load_from_data(Leaf(0), (), &leaf_storage));
+                Ok(ProcessingState::Loaded(d))
+            },
+            0,
+            &pool,
+            None,
+        );
+
+        let dependencies = dependent_storage.dependencies(&handle);
+        assert_eq!(1, dependencies.len());
+        assert_eq!("Leaf", dependencies[0].asset_type_name());
+        assert_eq!(leaf.unwrap().id(), dependencies[0].handle_id());
+    }
+
+    #[test]
+    fn reprocessing_replaces_recorded_dependencies() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::new(".", pool.clone());
+        let mut leaf_storage = AssetStorage::<Leaf>::new();
+        let mut dependent_storage = AssetStorage::<Dependent>::new();
+
+        let manual = leaf_storage.insert(Leaf(0));
+        let handle = loader.load_from_data(Dependent(0), (), &dependent_storage);
+        dependent_storage.add_dependency(&handle, leaf_storage.dependency(&manual));
+        let mut leaves = Vec::new();
+        for frame in 0..2 {
+            dependent_storage.processed.push(Processed::HotReload {
+                data: Ok(FormatValue::data(Dependent(1))),
+                handle: handle.clone(),
+                name: "<Data>".to_owned(),
+                old_reload: Box::new(ReloadTo(Dependent(1))),
+            });
+            dependent_storage.process(
+                |d| {
+                    leaves.push(loader.load_from_data(Leaf(0), (), &leaf_storage));
+                    Ok(ProcessingState::Loaded(d))
+                },
+                frame,
+                &pool,
+                None,
+            );
+        }
+
+        let dependencies = dependent_storage
+            .dependencies(&handle)
+            .iter()
+            .map(Dependency::handle_id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![manual.id(), leaves.last().unwrap().id()], dependencies);
+    }
+
+    #[test]
+    fn reloading_dependency_reloads_dependent() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let mut leaf_storage = AssetStorage::<Leaf>::new();
+        let mut dependent_storage = AssetStorage::<Dependent>::new();
+
+        let leaf = leaf_storage.insert(Leaf(0));
+        let dependent = push_new(&dependent_storage, Dependent(0), ReloadTo(Dependent(1)));
+        dependent_storage.add_dependency(&dependent, leaf_storage.dependency(&leaf));
+        dependent_storage.process(ProcessableAsset::process, 0, &pool, None);
+        assert_eq!(Some(&Dependent(0)), dependent_storage.get(&dependent));
+
+        leaf_storage.replace(&leaf, Leaf(1));
+
+        run_until(1, |frame| {
+            dependent_storage.process(ProcessableAsset::process, frame, &pool, None);
+            dependent_storage.get(&dependent) == Some(&Dependent(1))
+        });
+    }
+}
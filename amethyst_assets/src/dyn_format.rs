@@ -12,7 +12,11 @@ use serde::{
     ser::{Serialize, SerializeTupleStruct, Serializer},
     Deserialize, Deserializer,
 };
-use std::{collections::BTreeMap, marker::PhantomData};
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    marker::PhantomData,
+};
 
 /// A trait for all asset types that have their format types.
 /// Use this as a bound for asset data types when used inside boxed format types intended for deserialization.
@@ -62,6 +66,51 @@ pub struct Registry<T: ?Sized> {
     pub names: Vec<&'static str>,
 }
 
+// Not public API. Used by macros.
+//
+// Boxes a registered format into a `Box<dyn SerializableFormat<D>>`, so assets loaded with the
+// concrete format can be referred to with the boxed one, see `AssetStorage::format_origin`.
+#[doc(hidden)]
+pub struct FormatCast {
+    format: TypeId,
+    boxed: TypeId,
+    cast: fn(&dyn Any) -> Option<Box<dyn Any>>,
+}
+
+inventory::collect!(FormatCast);
+
+impl FormatCast {
+    #[doc(hidden)]
+    pub fn new<F, D>() -> Self
+    where
+        F: SerializableFormat<D>,
+        D: FormatRegisteredData,
+    {
+        FormatCast {
+            format: TypeId::of::<F>(),
+            boxed: TypeId::of::<Box<dyn SerializableFormat<D>>>(),
+            cast: |format| {
+                format.downcast_ref::<F>().map(|format| {
+                    let boxed: Box<dyn SerializableFormat<D>> = objekt::clone_box(format);
+                    Box::new(boxed) as Box<dyn Any>
+                })
+            },
+        }
+    }
+}
+
+/// Converts `format` to `B` if `B` is `Box<dyn SerializableFormat<D>>` and the concrete type of
+/// `format` is registered for `D` with `register_format`.
+pub(crate) fn cast_format<B: 'static>(format: &dyn Any) -> Option<B> {
+    let (format_type, boxed_type) = (format.type_id(), TypeId::of::<B>());
+    inventory::iter::<FormatCast>
+        .into_iter()
+        .filter(|cast| cast.format == format_type && cast.boxed == boxed_type)
+        .find_map(|cast| (cast.cast)(format))
+        .and_then(|boxed| boxed.downcast::<B>().ok())
+        .map(|boxed| *boxed)
+}
+
 pub struct SeqLookupVisitor<'a, T: ?Sized + 'static> {
     pub expected: &'a dyn Expected,
     pub registry: &'static Registry<T>,
@@ -269,6 +318,10 @@ macro_rules! register_format {
                 ),
             )
         }
+        $crate::inventory::submit!{
+            #![crate = $krate]
+            $crate::FormatCast::new::<$format, $data>()
+        }
         impl $crate::SerializableFormat<$data> for $format {}
     };
 }
@@ -321,4 +374,79 @@ mod tests {
             deserialized_prefab.test.import_simple(Vec::new()).unwrap()
         );
     }
+
+    #[cfg(any(
+        feature = "toml",
+        feature = "yaml",
+        feature = "msgpack",
+        feature = "bincode"
+    ))]
+    mod serde_formats {
+        use crate as amethyst_assets;
+        #[cfg(feature = "bincode")]
+        use crate::BincodeFormat;
+        #[cfg(feature = "msgpack")]
+        use crate::MessagePackFormat;
+        #[cfg(feature = "toml")]
+        use crate::TomlFormat;
+        #[cfg(feature = "yaml")]
+        use crate::YamlFormat;
+        use crate::{Format, SerializableFormat};
+
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
+        struct Config {
+            name: String,
+            size: u32,
+        }
+        register_format_type!(Config);
+
+        #[cfg(feature = "toml")]
+        register_format!("Toml", TomlFormat as Config);
+        #[cfg(feature = "yaml")]
+        register_format!("Yaml", YamlFormat as Config);
+        #[cfg(feature = "msgpack")]
+        register_format!("MessagePack", MessagePackFormat as Config);
+        #[cfg(feature = "bincode")]
+        register_format!("Bincode", BincodeFormat as Config);
+
+        fn config() -> Config {
+            Config {
+                name: "hero".to_owned(),
+                size: 3,
+            }
+        }
+
+        /// Deserializes the format tagged `name` and imports `bytes` with it.
+        fn import_tagged(name: &str, bytes: Vec<u8>) {
+            let tagged = format!("[{:?},null]", name);
+            let format: Box<dyn SerializableFormat<Config>> =
+                serde_json::from_str(&tagged).unwrap();
+            assert_eq!(tagged, serde_json::to_string(&format).unwrap());
+            assert_eq!(config(), format.import_simple(bytes).unwrap());
+        }
+
+        #[cfg(feature = "toml")]
+        #[test]
+        fn toml_format_is_registered() {
+            import_tagged("Toml", b"name = \"hero\"\nsize = 3\n".to_vec());
+        }
+
+        #[cfg(feature = "yaml")]
+        #[test]
+        fn yaml_format_is_registered() {
+            import_tagged("Yaml", b"name: hero\nsize: 3\n".to_vec());
+        }
+
+        #[cfg(feature = "msgpack")]
+        #[test]
+        fn msgpack_format_is_registered() {
+            import_tagged("MessagePack", rmp_serde::to_vec(&config()).unwrap());
+        }
+
+        #[cfg(feature = "bincode")]
+        #[test]
+        fn bincode_format_is_registered() {
+            import_tagged("Bincode", bincode::serialize(&config()).unwrap());
+        }
+    }
 }
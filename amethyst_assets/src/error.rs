@@ -8,9 +8,69 @@ pub enum Error {
     Source,
     #[error(display = "Format {:?} could not load asset", _0)]
     Format(&'static str),
+    #[error(
+        display = "Format {:?} could not load asset {:?} from source {:?}",
+        format,
+        name,
+        source_id
+    )]
+    Import {
+        name: String,
+        source_id: String,
+        format: &'static str,
+    },
     #[error(display = "Asset was loaded but no handle to it was saved.")]
     UnusedHandle,
+    #[error(display = "Loading the asset was cancelled")]
+    Cancelled,
     #[error(display = "Some error has occurred")]
     #[doc(hidden)]
     __Nonexhaustive,
 }
+
+/// Error for asset data which couldn't be parsed, pointing at the offending text.
+///
+/// Returned by text based formats like `RonFormat`, and found in the causes of failed
+/// assets with `AssetErrorMeta::parse_error`.
+#[derive(Clone, Debug, Error)]
+#[error(
+    display = "{} at line {}, column {}:\n{}",
+    message,
+    line,
+    column,
+    snippet
+)]
+pub struct ParseError {
+    /// What went wrong.
+    pub message: String,
+    /// Line of the offending text, starting at 1.
+    pub line: usize,
+    /// Column of the offending text, starting at 1.
+    pub column: usize,
+    /// The offending line, followed by a line marking the column.
+    pub snippet: String,
+}
+
+impl ParseError {
+    /// Creates a parse error at the given line and column of `bytes`.
+    pub fn new<M>(message: M, bytes: &[u8], line: usize, column: usize) -> Self
+    where
+        M: Into<String>,
+    {
+        let text = String::from_utf8_lossy(bytes);
+        let offending = text.lines().nth(line.saturating_sub(1)).unwrap_or("");
+        // keep tabs, so the marker lines up with the offending line
+        let indent: String = offending
+            .chars()
+            .take(column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        ParseError {
+            message: message.into(),
+            line,
+            column,
+            snippet: format!("{}\n{}^", offending, indent),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::marker::PhantomData;
+
+use derivative::Derivative;
+
+use crate::Handle;
+
+/// Event about an asset of type `A`, emitted by its `AssetStorage` while it's processed.
+///
+/// Systems can read the events of a storage to react to specific assets becoming available:
+///
+/// ```rust,ignore
+/// // in `SystemDesc::build`
+/// let reader = world.fetch_mut::<AssetStorage<Level>>().register_event_reader();
+///
+/// // in `System::run`
+/// for event in storage.events().read(&mut self.reader) {
+///     if event.is_for(&self.level) {
+///         if let AssetEvent::Loaded { .. } = event { /* ... */ }
+///     }
+/// }
+/// ```
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), PartialEq(bound = ""))]
+pub enum AssetEvent<A> {
+    /// The asset was loaded.
+    Loaded {
+        /// Id of the handle of the asset.
+        handle_id: u32,
+        /// Name the asset was loaded with.
+        name: String,
+    },
+    /// The asset was hot-reloaded, or reloaded because one of its dependencies changed.
+    Reloaded {
+        /// Id of the handle of the asset.
+        handle_id: u32,
+        /// Name the asset was loaded with.
+        name: String,
+    },
+    /// Loading the asset failed or was cancelled, or hot-reloading it failed, in which case the
+    /// previously loaded asset is kept.
+    Failed {
+        /// Id of the handle of the asset.
+        handle_id: u32,
+        /// Name the asset was loaded with.
+        name: String,
+        /// Description of the error.
+        error: String,
+    },
+    /// The asset was unloaded, because all handles to it were dropped or the storage was
+    /// cleared. Its handle id may be reused afterwards.
+    Unloaded {
+        /// Id of the handle of the asset.
+        handle_id: u32,
+    },
+    #[doc(hidden)]
+    __Nonexhaustive(PhantomData<A>),
+}
+
+impl<A> AssetEvent<A> {
+    /// Returns the id of the handle of the asset the event is about.
+    pub fn handle_id(&self) -> u32 {
+        match *self {
+            AssetEvent::Loaded { handle_id, .. }
+            | AssetEvent::Reloaded { handle_id, .. }
+            | AssetEvent::Failed { handle_id, .. }
+            | AssetEvent::Unloaded { handle_id } => handle_id,
+            AssetEvent::__Nonexhaustive(_) => unreachable!(),
+        }
+    }
+
+    /// Returns `true` if the event is about the asset behind `handle`.
+    pub fn is_for(&self, handle: &Handle<A>) -> bool {
+        self.handle_id() == handle.id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{mpsc, Arc};
+
+    use rayon::ThreadPoolBuilder;
+
+    use super::*;
+    use crate::{
+        test_util::{Level, UnitSource},
+        AssetStorage, Directory, Loader, ProcessableAsset, RonFormat,
+    };
+
+    #[test]
+    fn storage_emits_loaded_and_unloaded_events() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(Directory::new("assets"), pool.clone());
+        let mut storage = AssetStorage::<Level>::new();
+        let mut reader = storage.register_event_reader();
+
+        let handle = loader.load_from_data(Level, (), &storage);
+        storage.process(ProcessableAsset::process, 0, &pool, None);
+        let events = storage
+            .events()
+            .read(&mut reader)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![AssetEvent::Loaded {
+                handle_id: handle.id(),
+                name: "<Data>".to_owned(),
+            }],
+            events
+        );
+        assert!(events[0].is_for(&handle));
+
+        let handle_id = handle.id();
+        drop(handle);
+        storage.process(ProcessableAsset::process, 1, &pool, None);
+        let events = storage
+            .events()
+            .read(&mut reader)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(vec![AssetEvent::Unloaded { handle_id }], events);
+    }
+
+    #[test]
+    fn storage_emits_failed_events_for_cancelled_loads() {
+        let pool = Arc::new(ThreadPoolBuilder::new().num_threads(1).build().unwrap());
+        let loader = Loader::with_default_source(UnitSource, pool.clone());
+        let mut storage = AssetStorage::<Level>::new();
+        let mut reader = storage.register_event_reader();
+
+        // block the only worker thread, so the load is still waiting when it's cancelled
+        let (unblock, blocked) = mpsc::channel::<()>();
+        pool.spawn(move || blocked.recv().unwrap());
+        let handle = loader.load("intro.ron", RonFormat, (), &storage);
+        assert!(loader.cancel(&handle));
+        unblock.send(()).unwrap();
+
+        storage.process(ProcessableAsset::process, 0, &pool, None);
+        let events = storage
+            .events()
+            .read(&mut reader)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![AssetEvent::Failed {
+                handle_id: handle.id(),
+                name: "intro.ron".to_owned(),
+                error: "Loading the asset was cancelled".to_owned(),
+            }],
+            events
+        );
+        assert!(!storage.contains(&handle));
+    }
+}
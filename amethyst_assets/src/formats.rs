@@ -1,5 +1,7 @@
-use crate::Format;
-use amethyst_error::{format_err, Error, ResultExt};
+use crate::{Format, ParseError};
+#[cfg(any(feature = "msgpack", feature = "bincode"))]
+use amethyst_error::ResultExt;
+use amethyst_error::{format_err, Error};
 use serde::{Deserialize, Serialize};
 
 /// Format for loading from RON files. Mostly useful for prefabs.
@@ -21,19 +23,40 @@ where
 
     fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
         use ron::de::Deserializer;
-        let mut d = Deserializer::from_bytes(&bytes)
-            .with_context(|_| format_err!("Failed deserializing Ron file"))?;
-        let val =
-            D::deserialize(&mut d).with_context(|_| format_err!("Failed parsing Ron file"))?;
-        d.end()
-            .with_context(|_| format_err!("Failed parsing Ron file"))?;
+        let mut d = Deserializer::from_bytes(&bytes).map_err(|e| ron_error(e, &bytes))?;
+        let val = D::deserialize(&mut d).map_err(|e| ron_error(e, &bytes))?;
+        d.end().map_err(|e| ron_error(e, &bytes))?;
 
         Ok(val)
     }
 }
 
+/// Converts a Ron error to a `ParseError`, if it has a position.
+fn ron_error(error: ron::de::Error, bytes: &[u8]) -> Error {
+    let parse_error = match error {
+        ron::de::Error::Parser(ref kind, ref position) => Some(ParseError::new(
+            format!("{:?}", kind),
+            bytes,
+            position.line,
+            position.col,
+        )),
+        _ => None,
+    };
+    match parse_error {
+        Some(parse_error) => Error::new(parse_error).with_source(error),
+        None => format_err!("Failed parsing Ron file").with_source(error),
+    }
+}
+
 /// Format for loading from JSON files. Mostly useful for prefabs.
 /// This type can only be used as manually specified to the loader.
+///
+/// Like the other serde based formats, it can be registered for tagged
+/// deserialization of a specific data type:
+///
+/// ```rust,ignore
+/// amethyst_assets::register_format!("Json", JsonFormat as MyData);
+/// ```
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct JsonFormat;
 
@@ -48,12 +71,259 @@ where
 
     fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
         use serde_json::de::Deserializer;
+        let json_error = |e: serde_json::Error| {
+            Error::new(ParseError::new(e.to_string(), &bytes, e.line(), e.column()))
+        };
         let mut d = Deserializer::from_slice(&bytes);
-        let val = D::deserialize(&mut d)
-            .with_context(|_| format_err!("Failed deserializing Json file"))?;
-        d.end()
-            .with_context(|_| format_err!("Failed deserializing Json file"))?;
+        let val = D::deserialize(&mut d).map_err(json_error)?;
+        d.end().map_err(json_error)?;
+
+        Ok(val)
+    }
+}
+
+/// Format for loading from TOML files. Mostly useful for prefabs and configuration.
+///
+/// ```rust,ignore
+/// loader.load("prefab.toml", TomlFormat, ());
+/// amethyst_assets::register_format!("Toml", TomlFormat as MyData);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct TomlFormat;
+
+#[cfg(feature = "toml")]
+impl<D> Format<D> for TomlFormat
+where
+    D: for<'a> Deserialize<'a> + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "Toml"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        let val = toml::from_slice(&bytes).map_err(|e| match e.line_col() {
+            Some((line, column)) => {
+                Error::new(ParseError::new(e.to_string(), &bytes, line + 1, column + 1))
+            }
+            None => format_err!("Failed parsing Toml file").with_source(e),
+        })?;
+
+        Ok(val)
+    }
+}
+
+/// Format for loading from YAML files. Mostly useful for prefabs and configuration.
+///
+/// ```rust,ignore
+/// loader.load("prefab.yaml", YamlFormat, ());
+/// amethyst_assets::register_format!("Yaml", YamlFormat as MyData);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct YamlFormat;
+
+#[cfg(feature = "yaml")]
+impl<D> Format<D> for YamlFormat
+where
+    D: for<'a> Deserialize<'a> + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "Yaml"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        let val = serde_yaml::from_slice(&bytes).map_err(|e| match e.location() {
+            Some(location) => Error::new(ParseError::new(
+                e.to_string(),
+                &bytes,
+                location.line(),
+                location.column(),
+            )),
+            None => format_err!("Failed parsing Yaml file").with_source(e),
+        })?;
+
+        Ok(val)
+    }
+}
+
+/// Format for loading from binary MessagePack files, e.g. in cooked builds.
+///
+/// ```rust,ignore
+/// loader.load("prefab.msgpack", MessagePackFormat, ());
+/// amethyst_assets::register_format!("MessagePack", MessagePackFormat as MyData);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct MessagePackFormat;
+
+#[cfg(feature = "msgpack")]
+impl<D> Format<D> for MessagePackFormat
+where
+    D: for<'a> Deserialize<'a> + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "MessagePack"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        let val = rmp_serde::from_slice(&bytes)
+            .with_context(|_| format_err!("Failed deserializing MessagePack file"))?;
+
+        Ok(val)
+    }
+}
+
+/// Format for loading from binary bincode files, e.g. in cooked builds.
+///
+/// Bincode isn't self-describing, so data types relying on `deserialize_any`
+/// (like untagged enums) can't be loaded with it.
+///
+/// ```rust,ignore
+/// loader.load("prefab.bin", BincodeFormat, ());
+/// amethyst_assets::register_format!("Bincode", BincodeFormat as MyData);
+/// ```
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct BincodeFormat;
+
+#[cfg(feature = "bincode")]
+impl<D> Format<D> for BincodeFormat
+where
+    D: for<'a> Deserialize<'a> + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        "Bincode"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<D, Error> {
+        let val = bincode::deserialize(&bytes)
+            .with_context(|_| format_err!("Failed deserializing bincode file"))?;
 
         Ok(val)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rayon::ThreadPoolBuilder;
+    use serde::{Deserialize, Serialize};
+
+    use amethyst_core::ecs::VecStorage;
+
+    use super::*;
+    use crate::{
+        test_util::process_until, Asset, AssetStorage, Format, Handle, Loader, ProgressCounter,
+        Source,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Data {
+        name: String,
+        values: Vec<u32>,
+    }
+
+    fn data() -> Data {
+        Data {
+            name: "test".to_owned(),
+            values: vec![1, 2, 3],
+        }
+    }
+
+    fn import<F: Format<Data>>(format: F, bytes: &[u8]) -> Data {
+        format.import_simple(bytes.to_vec()).unwrap()
+    }
+
+    impl Asset for Data {
+        const NAME: &'static str = "Data";
+        type Data = Self;
+        type HandleStorage = VecStorage<Handle<Self>>;
+    }
+
+    /// Ron data missing a comma in line 3.
+    const BROKEN_RON: &[u8] = b"(\n    name: \"test\",\n    values: [1, 2 3],\n)";
+
+    /// Source serving broken Ron data.
+    struct BrokenSource;
+
+    impl Source for BrokenSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            Ok(BROKEN_RON.to_vec())
+        }
+    }
+
+    fn parse_error(error: &Error) -> Option<&ParseError> {
+        error
+            .causes()
+            .filter_map(|e| e.as_error().downcast_ref())
+            .next()
+    }
+
+    #[test]
+    fn ron_format_imports_data() {
+        assert_eq!(
+            data(),
+            import(RonFormat, b"(name: \"test\", values: [1, 2, 3])")
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_format_imports_data() {
+        assert_eq!(
+            data(),
+            import(TomlFormat, b"name = \"test\"\nvalues = [1, 2, 3]\n")
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_format_imports_data() {
+        assert_eq!(
+            data(),
+            import(YamlFormat, b"name: test\nvalues: [1, 2, 3]\n")
+        );
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn message_pack_format_imports_data() {
+        let bytes = rmp_serde::to_vec(&data()).unwrap();
+        assert_eq!(data(), import(MessagePackFormat, &bytes));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bincode_format_imports_data() {
+        let bytes = bincode::serialize(&data()).unwrap();
+        assert_eq!(data(), import(BincodeFormat, &bytes));
+    }
+
+    #[test]
+    fn ron_format_reports_position_of_parse_errors() {
+        let error = <RonFormat as Format<Data>>::import_simple(&RonFormat, BROKEN_RON.to_vec())
+            .unwrap_err();
+        let parse_error = parse_error(&error).expect("No parse error");
+        assert_eq!(3, parse_error.line);
+        assert!(parse_error.snippet.starts_with("    values: [1, 2 3],\n"));
+    }
+
+    #[test]
+    fn failed_loads_report_asset_source_format_and_position() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(BrokenSource, pool.clone());
+        let mut storage = AssetStorage::<Data>::new();
+        let mut progress = ProgressCounter::new();
+        let _handle = loader.load("data.ron", RonFormat, &mut progress, &storage);
+        process_until(&mut storage, &pool, |_| progress.num_loading() == 0);
+
+        let errors = progress.errors();
+        assert_eq!(1, errors.len());
+        assert_eq!("data.ron", errors[0].asset_name);
+        assert_eq!(Some(""), errors[0].source_id());
+        assert_eq!(Some("Ron"), errors[0].format_name());
+        assert_eq!(Some(3), errors[0].parse_error().map(|e| e.line));
+    }
+}
@@ -4,8 +4,12 @@ use amethyst_core::ecs::{
     shred::{ResourceId, SystemData},
     Read, ReadExpect, World,
 };
+use amethyst_error::Error;
+use fnv::FnvHashMap;
 
-use crate::{Asset, AssetStorage, Format, Handle, Loader, Progress};
+use crate::{
+    Asset, AssetRef, AssetStorage, Cache, Format, Handle, Loader, Progress, ProgressCounter,
+};
 
 /// Helper type for loading assets
 #[derive(SystemData)]
@@ -57,6 +61,87 @@ where
             .load_from(name, format, source, progress, &*self.storage)
     }
 
+    /// Loads an asset with a given format and priority from the default (directory) source.
+    ///
+    /// See `Loader::load_from_with_priority` for more information.
+    pub fn load_with_priority<F, N, P>(
+        &self,
+        name: N,
+        format: F,
+        priority: i32,
+        progress: P,
+    ) -> Handle<A>
+    where
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+    {
+        self.loader
+            .load_with_priority(name, format, priority, progress, &*self.storage)
+    }
+
+    /// Loads all assets of the default source matching the glob `pattern`, returning their
+    /// handles by path.
+    ///
+    /// See `Loader::load_matching` for more information.
+    pub fn load_matching<F>(
+        &self,
+        pattern: &str,
+        format: F,
+        progress: &mut ProgressCounter,
+    ) -> Result<FnvHashMap<String, Handle<A>>, Error>
+    where
+        F: Format<A::Data>,
+    {
+        self.loader
+            .load_matching(pattern, format, "", progress, &*self.storage)
+    }
+
+    /// Loads an asset by logical id like `load`, unless `cache` has a live handle for it.
+    ///
+    /// The cache is keyed by the id after following the aliases of the `Loader`'s manifest,
+    /// so an alias and the id it refers to share the same handle.
+    pub fn load_cached<F, P>(
+        &self,
+        id: &str,
+        format: F,
+        progress: P,
+        cache: &mut Cache<A>,
+    ) -> Handle<A>
+    where
+        F: Format<A::Data>,
+        P: Progress,
+    {
+        let id = self.loader.canonical_id(id);
+        if let Some(handle) = cache.get(id) {
+            return handle;
+        }
+        let handle = self.load(id, format, progress);
+        cache.insert(id, &handle);
+        handle
+    }
+
+    /// Returns a handle to the asset `asset_ref` refers to, loading it if it isn't loaded yet.
+    ///
+    /// See `AssetRef::load` for more information.
+    pub fn load_ref<F, P>(&self, asset_ref: &mut AssetRef<A, F>, progress: P) -> Handle<A>
+    where
+        F: Format<A::Data>,
+        P: Progress,
+    {
+        asset_ref.load(&self.loader, progress, &self.storage)
+    }
+
+    /// Changes the priority of a pending load, see `Loader::set_priority`.
+    pub fn set_priority(&self, handle: &Handle<A>, priority: i32) -> bool {
+        self.loader.set_priority(handle, priority)
+    }
+
+    /// Cancels a pending load, see `Loader::cancel`.
+    pub fn cancel(&self, handle: &Handle<A>) -> bool {
+        self.loader.cancel(handle)
+    }
+
     /// Load an asset from data and return a handle.
     pub fn load_from_data<P>(&self, data: A::Data, progress: P) -> Handle<A>
     where
@@ -5,46 +5,78 @@
 //!
 //! * Extensibility
 //! * Asynchronous & Parallel using Rayon
-//! * Allow different sources
+//! * Allow different sources (directories, pak archives, ...)
 
 #![warn(missing_docs, rust_2018_idioms, rust_2018_compatibility)]
 
+#[cfg(feature = "cook")]
+pub use crate::cook::{CookReport, Cooked, Cooker, ImportCache};
+#[cfg(feature = "bincode")]
+pub use crate::formats::BincodeFormat;
 #[cfg(feature = "json")]
 pub use crate::formats::JsonFormat;
+#[cfg(feature = "msgpack")]
+pub use crate::formats::MessagePackFormat;
+#[cfg(feature = "toml")]
+pub use crate::formats::TomlFormat;
+#[cfg(feature = "yaml")]
+pub use crate::formats::YamlFormat;
 pub use crate::{
     asset::{Asset, Format, FormatValue, ProcessableAsset, SerializableFormat},
+    asset_ref::AssetRef,
     cache::Cache,
+    compression::{Compressed, Compression},
+    dependency::Dependency,
     dyn_format::FormatRegisteredData,
+    error::ParseError,
+    event::AssetEvent,
     formats::RonFormat,
     helper::AssetLoaderSystemData,
     loader::Loader,
+    manifest::{Manifest, ManifestEntry},
     prefab::{
-        AssetPrefab, Prefab, PrefabData, PrefabLoader, PrefabLoaderSystem, PrefabLoaderSystemDesc,
+        AssetPrefab, Prefab, PrefabData, PrefabExtract, PrefabInstance, PrefabInstances,
+        PrefabLoader, PrefabLoaderSystem, PrefabLoaderSystemDesc, PrefabOverrides,
+    },
+    progress::{
+        AssetErrorMeta, AssetTiming, Callback, Completion, Progress, ProgressCounter, Tracker,
     },
-    progress::{Completion, Progress, ProgressCounter, Tracker},
     reload::{HotReloadBundle, HotReloadStrategy, HotReloadSystem, Reload, SingleFile},
-    source::{Directory, Source},
+    source::{Archive, ArchiveBuilder, Directory, Overlay, Source},
+    stats::{AssetStats, AssetStorageStats},
     storage::{AssetStorage, Handle, ProcessingState, Processor, WeakHandle},
 };
 
 pub use rayon::ThreadPool;
 
 mod asset;
+mod asset_ref;
 mod cache;
+mod compression;
+#[cfg(feature = "cook")]
+mod cook;
+mod dependency;
 mod dyn_format;
 mod error;
+mod event;
 mod formats;
 mod helper;
 mod loader;
+mod manifest;
 mod prefab;
 mod progress;
+mod queue;
 mod reload;
+mod residency;
 mod source;
+mod stats;
 mod storage;
+#[cfg(test)]
+mod test_util;
 
 // used in macros. Private API otherwise.
 #[doc(hidden)]
-pub use crate::dyn_format::{DeserializeFn, Registry};
+pub use crate::dyn_format::{DeserializeFn, FormatCast, Registry};
 // used in macros. Private API otherwise.
 #[doc(hidden)]
 pub use {erased_serde, inventory, lazy_static};
@@ -1,23 +1,45 @@
-use std::{borrow::Borrow, hash::Hash, path::PathBuf, sync::Arc};
+use std::{
+    any::TypeId,
+    borrow::Borrow,
+    hash::Hash,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 use fnv::FnvHashMap;
 use log::debug;
 use rayon::ThreadPool;
 
-use amethyst_error::ResultExt;
+use amethyst_error::{format_err, ResultExt};
+#[cfg(feature = "cook")]
+use serde::{de::DeserializeOwned, Serialize};
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
 use crate::{
+    dependency,
     error::Error,
+    progress::Tracker,
+    queue::LoadQueue,
+    source::Pattern,
     storage::{AssetStorage, Handle, Processed},
-    Asset, Directory, Format, FormatValue, Progress, Source,
+    Asset, Directory, Format, FormatValue, Manifest, Progress, ProgressCounter, Source,
 };
+#[cfg(feature = "cook")]
+use crate::{Cooked, ImportCache};
 
 /// The asset loader, holding the sources and a reference to the `ThreadPool`.
 pub struct Loader {
+    bytes_loaded: Arc<AtomicUsize>,
     hot_reload: bool,
+    #[cfg(feature = "cook")]
+    import_cache: Option<Arc<ImportCache>>,
+    manifest: Manifest,
     pool: Arc<ThreadPool>,
+    queue: LoadQueue,
     sources: FnvHashMap<String, Arc<dyn Source>>,
 }
 
@@ -37,8 +59,13 @@ impl Loader {
         S: Source,
     {
         let mut loader = Loader {
+            bytes_loaded: Default::default(),
             hot_reload: true,
+            #[cfg(feature = "cook")]
+            import_cache: None,
+            manifest: Default::default(),
             pool,
+            queue: Default::default(),
             sources: Default::default(),
         };
 
@@ -64,6 +91,37 @@ impl Loader {
         self.add_source(String::new(), source);
     }
 
+    /// Sets the manifest mapping logical asset ids to the files they're loaded from.
+    ///
+    /// See `load_from_with_priority` for how ids are resolved.
+    pub fn set_manifest(&mut self, manifest: Manifest) {
+        self.manifest = manifest;
+    }
+
+    /// Returns the manifest mapping logical asset ids to the files they're loaded from.
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Loads a manifest in the Ron format from `path` in the default source and merges it
+    /// into the current one, replacing the entries and aliases with the same ids.
+    ///
+    /// This allows loading a manifest per platform or quality level on top of a common one.
+    pub fn load_manifest(&mut self, path: &str) -> Result<(), amethyst_error::Error> {
+        let manifest = Manifest::load(&*self.source(""), path)?;
+        self.manifest.merge(manifest);
+
+        Ok(())
+    }
+
+    /// Returns the id `id` refers to after following the aliases of the manifest.
+    ///
+    /// Use this to key a `Cache` of assets loaded by logical id, so an alias and the id it
+    /// refers to share the same entry.
+    pub fn canonical_id<'a>(&'a self, id: &'a str) -> &'a str {
+        self.manifest.canonical_id(id)
+    }
+
     /// If set to `true`, this `Loader` will ask formats to
     /// generate "reload instructions" which *allow* reloading.
     /// Calling `set_hot_reload(true)` does not actually enable
@@ -73,6 +131,53 @@ impl Loader {
         self.hot_reload = value;
     }
 
+    /// Sets the cache used by `load_cooked`, or disables cooking if `None`.
+    #[cfg(feature = "cook")]
+    pub fn set_import_cache(&mut self, cache: Option<ImportCache>) {
+        self.import_cache = cache.map(Arc::new);
+    }
+
+    /// Returns the cache used by `load_cooked`.
+    #[cfg(feature = "cook")]
+    pub fn import_cache(&self) -> Option<&ImportCache> {
+        self.import_cache.as_ref().map(|cache| &**cache)
+    }
+
+    /// Loads an asset like `load_from`, but reads the data from the import cache if
+    /// it was cooked from the same bytes. Otherwise, the asset is imported with `format`
+    /// and the data is stored in the cache.
+    ///
+    /// Without an import cache (see `set_import_cache`), this is the same as `load_from`.
+    #[cfg(feature = "cook")]
+    pub fn load_cooked<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        A::Data: Serialize + DeserializeOwned,
+        F: Format<A::Data> + Clone,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        match self.import_cache {
+            Some(ref cache) => self.load_from(
+                name,
+                Cooked::new(format, cache.clone()),
+                source,
+                progress,
+                storage,
+            ),
+            None => self.load_from(name, format, source, progress, storage),
+        }
+    }
+
     /// Loads an asset with a given format from the default (directory) source.
     /// If you want to load from a custom source instead, use `load_from`.
     ///
@@ -108,11 +213,69 @@ impl Loader {
     /// * `progress`: A tracker which will be notified of assets which have been imported
     /// * `storage`: The asset storage which can be fetched from the ECS `World` using
     ///   `read_resource`.
+    ///
+    /// If the storage has a memory budget and the asset is still resident, the existing
+    /// handle is returned and the progress completes when the load of that handle completes,
+    /// right away if it's loaded already.
+    ///
+    /// The load is queued with priority `0`, see `load_from_with_priority`.
     pub fn load_from<A, F, N, P, S>(
         &self,
         name: N,
         format: F,
         source: &S,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        self.load_from_with_priority(name, format, source, 0, progress, storage)
+    }
+
+    /// Loads an asset with a given format and priority from the default (directory) source.
+    ///
+    /// See `load_from_with_priority` for more information.
+    pub fn load_with_priority<A, F, N, P>(
+        &self,
+        name: N,
+        format: F,
+        priority: i32,
+        progress: P,
+        storage: &AssetStorage<A>,
+    ) -> Handle<A>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        N: Into<String>,
+        P: Progress,
+    {
+        self.load_from_with_priority::<A, F, _, _, _>(name, format, "", priority, progress, storage)
+    }
+
+    /// Loads an asset with a given id, format and priority from a custom source.
+    ///
+    /// Loads wait in a queue until a worker thread is free to run them; the pending
+    /// load with the highest `priority` is run first, and loads with the same priority
+    /// are run in the order they were requested. Pending loads can be re-prioritised
+    /// with `set_priority` or cancelled with `cancel`.
+    ///
+    /// If the manifest has an entry for `name` (or an alias of it), the asset is loaded from
+    /// the path of the entry instead, and from its source if it names one. If the entry names
+    /// a format other than `format` or a source which wasn't added, the load fails.
+    ///
+    /// See `load_from` for the other parameters.
+    pub fn load_from_with_priority<A, F, N, P, S>(
+        &self,
+        name: N,
+        format: F,
+        source: &S,
+        priority: i32,
         mut progress: P,
         storage: &AssetStorage<A>,
     ) -> Handle<A>
@@ -126,18 +289,59 @@ impl Loader {
     {
         #[cfg(feature = "profiler")]
         profile_scope!("load_asset_from");
-        use crate::progress::Tracker;
 
         let name = name.into();
-        let source = source.as_ref();
+        let requested_source = source.as_ref();
+        let mut source = requested_source;
+        let mut required_format = None;
+        let mut manifest_source = false;
+        let requested_name = name.clone();
+        let name = match self.manifest.resolve(&name) {
+            Some(entry) => {
+                debug!(
+                    "{:?}: Resolved asset id {:?} to {:?} with manifest",
+                    A::NAME,
+                    name,
+                    entry.path,
+                );
+                if !entry.source.is_empty() {
+                    source = &entry.source;
+                    manifest_source = true;
+                }
+                required_format = entry.format.as_ref();
+                entry.path.clone()
+            }
+            None => name,
+        };
 
         let format_name = format.name();
+        let format_mismatch = required_format
+            .filter(|required| *required != format_name)
+            .cloned();
         let source_name = match source {
             "" => "[default source]",
             other => other,
         };
 
+        if let Some(handle) = storage.resident(source, &name) {
+            debug!(
+                "{:?}: Asset {:?} from source {:?} is resident (handle id: {:?})",
+                A::NAME,
+                name,
+                source_name,
+                handle,
+            );
+            dependency::record(|| storage.dependency(&handle));
+            progress.add_assets(1);
+            storage.track(&handle, Box::new(progress.create_tracker()));
+
+            return handle;
+        }
+
         let handle = storage.allocate();
+        storage.make_resident(source, &name, &handle);
+        storage.set_origin(requested_source, &requested_name, &format, &handle);
+        dependency::record(|| storage.dependency(&handle));
 
         debug!(
             "{:?}: Loading asset {:?} with format {:?} from source {:?} (handle id: {:?})",
@@ -151,9 +355,16 @@ impl Loader {
         progress.add_assets(1);
         let tracker = progress.create_tracker();
 
-        let source = self.source(source);
+        let source_id = source.to_owned();
+        let source = if manifest_source {
+            self.sources.get(source).cloned()
+        } else {
+            Some(self.source(source))
+        };
         let handle_clone = handle.clone();
         let processed = storage.processed.clone();
+        let load_stats = storage.load_stats.clone();
+        let bytes_loaded = self.bytes_loaded.clone();
 
         let hot_reload = if self.hot_reload {
             Some(objekt::clone_box(&format) as Box<dyn Format<A::Data>>)
@@ -161,13 +372,37 @@ impl Loader {
             None
         };
 
-        let cl = move || {
+        let cl = move |cancelled| {
+            let mut tracker = Box::new(tracker) as Box<dyn Tracker>;
+            if cancelled {
+                tracker.cancel(handle.id(), A::NAME, name);
+                processed.push(Processed::Cancelled { handle });
+                return;
+            }
+
             #[cfg(feature = "profiler")]
             profile_scope!("load_asset_from_worker");
-            let data = format
-                .import(name.clone(), source, hot_reload)
-                .with_context(|_| Error::Format(format_name));
-            let tracker = Box::new(tracker) as Box<dyn Tracker>;
+            let source = source.map(|source| Arc::new(CountingSource::new(source)));
+            let data = match (format_mismatch, &source) {
+                (Some(required), _) => Err(format_err!(
+                    "The asset manifest requires format {:?}",
+                    required
+                )),
+                (None, None) => Err(format_err!(
+                    "The asset manifest requires source {:?}, which wasn't added to the loader",
+                    source_id
+                )),
+                (None, Some(source)) => format.import(name.clone(), source.clone(), hot_reload),
+            }
+            .with_context(|_| Error::Import {
+                name: name.clone(),
+                source_id,
+                format: format_name,
+            });
+            let bytes_read = source.map_or(0, |source| source.bytes_read());
+            tracker.bytes_read(A::NAME, &name, bytes_read);
+            load_stats.imported(handle.id(), bytes_read);
+            bytes_loaded.fetch_add(bytes_read, Ordering::Relaxed);
 
             processed.push(Processed::NewAsset {
                 data,
@@ -176,11 +411,53 @@ impl Loader {
                 tracker,
             });
         };
-        self.pool.spawn(cl);
+        self.queue
+            .push(&self.pool, Self::load_key(&handle_clone), priority, cl);
 
         handle_clone
     }
 
+    /// Loads all assets of `source` matching the glob `pattern` with the given format,
+    /// returning their handles by path.
+    ///
+    /// In the pattern, `?` matches any character except `/`, `*` matches any number of
+    /// characters except `/` and `**` matches any number of characters including `/`,
+    /// e.g. `"sfx/*.ogg"` or `"levels/**/*.ron"`.
+    ///
+    /// ## Errors
+    ///
+    /// If the source can't list its assets, see `Source::list`.
+    pub fn load_matching<A, F, S>(
+        &self,
+        pattern: &str,
+        format: F,
+        source: &S,
+        progress: &mut ProgressCounter,
+        storage: &AssetStorage<A>,
+    ) -> Result<FnvHashMap<String, Handle<A>>, amethyst_error::Error>
+    where
+        A: Asset,
+        F: Format<A::Data>,
+        S: AsRef<str> + Eq + Hash + ?Sized,
+        String: Borrow<S>,
+    {
+        let matcher = Pattern::new(pattern);
+        let paths = self
+            .source(source.as_ref())
+            .list(&matcher.dir())
+            .with_context(|_| format_err!("Failed listing assets matching {:?}", pattern))?;
+
+        Ok(paths
+            .into_iter()
+            .filter(|path| matcher.matches(path))
+            .map(|path| {
+                let format = objekt::clone_box(&format) as Box<dyn Format<A::Data>>;
+                let handle = self.load_from(path.clone(), format, source, &mut *progress, storage);
+                (path, handle)
+            })
+            .collect())
+    }
+
     /// Load an asset from data and return a handle.
     pub fn load_from_data<A, P>(
         &self,
@@ -196,6 +473,7 @@ impl Loader {
         let tracker = progress.create_tracker();
         let tracker = Box::new(tracker);
         let handle = storage.allocate();
+        dependency::record(|| storage.dependency(&handle));
         storage.processed.push(Processed::NewAsset {
             data: Ok(FormatValue::data(data)),
             handle: handle.clone(),
@@ -222,14 +500,21 @@ impl Loader {
         let tracker = progress.create_tracker();
         let tracker = Box::new(tracker);
         let handle = storage.allocate();
+        dependency::record(|| storage.dependency(&handle));
         let processed = storage.processed.clone();
 
-        self.pool.spawn({
+        self.queue.push(&self.pool, Self::load_key(&handle), 0, {
             let handle = handle.clone();
-            move || {
+            move |cancelled| {
+                if cancelled {
+                    tracker.cancel(handle.id(), A::NAME, "<Data>".into());
+                    processed.push(Processed::Cancelled { handle });
+                    return;
+                }
+
                 processed.push(Processed::NewAsset {
                     data: Ok(FormatValue::data(data())),
-                    handle: handle.clone(),
+                    handle,
                     name: "<Data>".into(),
                     tracker,
                 });
@@ -239,6 +524,50 @@ impl Loader {
         handle
     }
 
+    /// Changes the priority of the pending load of the asset with the given handle.
+    ///
+    /// Returns `false` if the asset isn't waiting to be loaded, e.g. because loading
+    /// already started or finished.
+    pub fn set_priority<A: Asset>(&self, handle: &Handle<A>, priority: i32) -> bool {
+        self.queue.set_priority(Self::load_key(handle), priority)
+    }
+
+    /// Cancels the pending load of the asset with the given handle.
+    ///
+    /// The progress of the load is notified with `Tracker::cancel` and the handle
+    /// will never point to a loaded asset. Returns `false` if the asset isn't waiting
+    /// to be loaded, e.g. because loading already started or finished.
+    pub fn cancel<A: Asset>(&self, handle: &Handle<A>) -> bool {
+        self.queue.cancel(Self::load_key(handle))
+    }
+
+    /// Returns the number of loads which are waiting for a worker thread.
+    pub fn num_pending(&self) -> usize {
+        self.queue.num_pending()
+    }
+
+    /// Returns the number of bytes read from sources by all loads so far.
+    ///
+    /// See `AssetStorage::stats` for the bytes read per asset type.
+    pub fn bytes_loaded(&self) -> usize {
+        self.bytes_loaded.load(Ordering::Relaxed)
+    }
+
+    fn load_key<A: Asset>(handle: &Handle<A>) -> (TypeId, u32) {
+        (TypeId::of::<A>(), handle.id())
+    }
+
+    /// Collects the paths reported as changed by all sources supporting change notifications.
+    ///
+    /// See `Source::poll_changes`.
+    pub(crate) fn poll_changes(&self) -> Vec<String> {
+        self.sources
+            .values()
+            .filter_map(|source| source.poll_changes())
+            .flatten()
+            .collect()
+    }
+
     fn source(&self, source: &str) -> Arc<dyn Source> {
         self.sources
             .get(source)
@@ -246,3 +575,42 @@ impl Loader {
             .clone()
     }
 }
+
+/// Wraps the source of a load to count the bytes the format reads from it.
+struct CountingSource {
+    bytes_read: AtomicUsize,
+    inner: Arc<dyn Source>,
+}
+
+impl CountingSource {
+    fn new(inner: Arc<dyn Source>) -> Self {
+        CountingSource {
+            bytes_read: AtomicUsize::new(0),
+            inner,
+        }
+    }
+
+    fn bytes_read(&self) -> usize {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+}
+
+impl Source for CountingSource {
+    fn modified(&self, path: &str) -> Result<u64, amethyst_error::Error> {
+        self.inner.modified(path)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, amethyst_error::Error> {
+        let bytes = self.inner.load(path)?;
+        self.bytes_read.fetch_add(bytes.len(), Ordering::Relaxed);
+
+        Ok(bytes)
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), amethyst_error::Error> {
+        let (bytes, modified) = self.inner.load_with_metadata(path)?;
+        self.bytes_read.fetch_add(bytes.len(), Ordering::Relaxed);
+
+        Ok((bytes, modified))
+    }
+}
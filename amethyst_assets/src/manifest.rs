@@ -0,0 +1,248 @@
+//! Mapping logical asset ids to the files they're loaded from.
+
+use fnv::FnvHashMap;
+use serde::{Deserialize, Serialize};
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{Format, RonFormat, Source};
+
+/// Maps logical asset ids like `"ui/main_font"` to the path, source and format they're
+/// loaded from, so content can be moved or swapped without changing code.
+///
+/// A manifest is usually loaded from a Ron file with `Loader::load_manifest`:
+///
+/// ```ron
+/// (
+///     assets: {
+///         "ui/main_font": (path: "font/square.ttf", format: Some("TTF")),
+///         "level/intro": (path: "levels/intro.ron", source: "pak"),
+///     },
+///     aliases: {
+///         "font": "ui/main_font",
+///     },
+/// )
+/// ```
+///
+/// Loading another manifest on top of it (e.g. one per platform or quality level) replaces
+/// the entries and aliases with the same ids, see `merge`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Manifest {
+    assets: FnvHashMap<String, ManifestEntry>,
+    aliases: FnvHashMap<String, String>,
+}
+
+/// Where the asset with a logical id is loaded from.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ManifestEntry {
+    /// The path of the asset within its source.
+    pub path: String,
+    /// The id of the source, the default source if empty.
+    #[serde(default)]
+    pub source: String,
+    /// The name of the format the asset has to be loaded with, if any.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+impl ManifestEntry {
+    /// Creates an entry for the file at `path` in the default source.
+    pub fn new<P: Into<String>>(path: P) -> Self {
+        ManifestEntry {
+            path: path.into(),
+            source: String::new(),
+            format: None,
+        }
+    }
+
+    /// Loads the asset from the source with id `source` instead.
+    pub fn with_source<S: Into<String>>(mut self, source: S) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Requires the asset to be loaded with the format named `format`.
+    pub fn with_format<F: Into<String>>(mut self, format: F) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+}
+
+/// Aliases pointing to each other are followed at most this many times.
+const MAX_ALIAS_DEPTH: usize = 16;
+
+impl Manifest {
+    /// Creates an empty manifest.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Loads a manifest in the Ron format from `path` in `source`.
+    pub fn load(source: &dyn Source, path: &str) -> Result<Self, Error> {
+        let bytes = source
+            .load(path)
+            .with_context(|_| format_err!("Failed loading asset manifest {:?}", path))?;
+        <RonFormat as Format<Self>>::import_simple(&RonFormat, bytes)
+            .with_context(|_| format_err!("Failed parsing asset manifest {:?}", path))
+    }
+
+    /// Adds an entry for the logical id `id` and returns the old one (if any).
+    pub fn insert<I: Into<String>>(
+        &mut self,
+        id: I,
+        entry: ManifestEntry,
+    ) -> Option<ManifestEntry> {
+        self.assets.insert(id.into(), entry)
+    }
+
+    /// Makes `alias` refer to the same asset as `id`.
+    pub fn alias<A, I>(&mut self, alias: A, id: I)
+    where
+        A: Into<String>,
+        I: Into<String>,
+    {
+        self.aliases.insert(alias.into(), id.into());
+    }
+
+    /// Adds the entries and aliases of `other`, replacing the ones with the same ids.
+    pub fn merge(&mut self, other: Manifest) {
+        self.assets.extend(other.assets);
+        self.aliases.extend(other.aliases);
+    }
+
+    /// Returns the id `id` refers to after following its aliases.
+    ///
+    /// Ids which aren't aliases are returned unchanged, so this can be used to key caches of
+    /// assets loaded by id.
+    pub fn canonical_id<'a>(&'a self, mut id: &'a str) -> &'a str {
+        for _ in 0..MAX_ALIAS_DEPTH {
+            match self.aliases.get(id) {
+                Some(target) => id = target,
+                None => break,
+            }
+        }
+        id
+    }
+
+    /// Returns the entry for the logical id or alias `id`, if the manifest has one.
+    pub fn resolve(&self, id: &str) -> Option<&ManifestEntry> {
+        self.assets.get(self.canonical_id(id))
+    }
+
+    /// Returns `true` if the manifest has no entries and no aliases.
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty() && self.aliases.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rayon::ThreadPoolBuilder;
+
+    use super::*;
+    use crate::{
+        test_util::{process_until, Level},
+        AssetStorage, Completion, Loader, ProgressCounter,
+    };
+
+    const MANIFEST: &[u8] = br#"(
+        assets: {
+            "ui/main_font": (path: "font/square.ttf"),
+            "level/intro": (path: "levels/intro.ron", source: "pak", format: Some("Ron")),
+        },
+        aliases: {
+            "font": "ui/main_font",
+            "default_font": "font",
+        },
+    )"#;
+
+    struct ManifestSource;
+
+    impl Source for ManifestSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+            match path {
+                "manifest.ron" => Ok(MANIFEST.to_vec()),
+                _ => Err(format_err!("No such file {:?}", path)),
+            }
+        }
+    }
+
+    #[test]
+    fn aliases_resolve_to_entries() {
+        let manifest = Manifest::load(&ManifestSource, "manifest.ron").unwrap();
+
+        assert_eq!("ui/main_font", manifest.canonical_id("default_font"));
+        assert_eq!("unknown", manifest.canonical_id("unknown"));
+        assert_eq!(
+            Some(&ManifestEntry::new("font/square.ttf")),
+            manifest.resolve("default_font")
+        );
+        assert_eq!(
+            Some(
+                &ManifestEntry::new("levels/intro.ron")
+                    .with_source("pak")
+                    .with_format("Ron")
+            ),
+            manifest.resolve("level/intro")
+        );
+        assert_eq!(None, manifest.resolve("unknown"));
+    }
+
+    #[test]
+    fn merged_manifests_replace_entries() {
+        let mut manifest = Manifest::load(&ManifestSource, "manifest.ron").unwrap();
+        let mut low_quality = Manifest::new();
+        low_quality.insert("ui/main_font", ManifestEntry::new("font/square_small.ttf"));
+        manifest.merge(low_quality);
+
+        assert_eq!(
+            Some(&ManifestEntry::new("font/square_small.ttf")),
+            manifest.resolve("font")
+        );
+    }
+
+    #[test]
+    fn loader_loads_ids_from_their_manifest_path() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let mut loader = Loader::with_default_source(ManifestSource, pool);
+        loader.add_source("pak", ManifestSource);
+        loader.load_manifest("manifest.ron").unwrap();
+        let storage = AssetStorage::<Level>::new();
+
+        let font = loader.load("font", RonFormat, (), &storage);
+        let level = loader.load("level/intro", RonFormat, (), &storage);
+
+        // the origins are the requested ids, so they're resolved again when loading them later
+        assert_eq!(
+            Some(("".to_owned(), "font".to_owned())),
+            storage.origin(&font)
+        );
+        assert_eq!(
+            Some(("".to_owned(), "level/intro".to_owned())),
+            storage.origin(&level)
+        );
+        assert_eq!(Some(level), storage.loaded_from("", "level/intro"));
+    }
+
+    #[test]
+    fn entries_naming_missing_sources_fail_to_load() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let mut loader = Loader::with_default_source(ManifestSource, pool.clone());
+        loader.load_manifest("manifest.ron").unwrap();
+        let mut storage = AssetStorage::<Level>::new();
+
+        let mut progress = ProgressCounter::new();
+        loader.load("level/intro", RonFormat, &mut progress, &storage);
+        process_until(&mut storage, &pool, |_| {
+            progress.complete() != Completion::Loading
+        });
+        assert_eq!(Completion::Failed, progress.complete());
+    }
+}
@@ -4,7 +4,7 @@ use amethyst_core::{
 };
 use amethyst_error::Error;
 
-use crate::{PrefabData, ProgressCounter};
+use crate::{PrefabData, PrefabExtract, ProgressCounter};
 
 impl<'a, T> PrefabData<'a> for Option<T>
 where
@@ -45,6 +45,19 @@ where
     }
 }
 
+impl<'a, T> PrefabExtract<'a> for Option<T>
+where
+    T: PrefabExtract<'a>,
+{
+    fn extract(
+        entity: Entity,
+        system_data: &mut Self::SystemData,
+        entities: &[Entity],
+    ) -> Result<Option<Self>, Error> {
+        Ok(Some(T::extract(entity, system_data, entities)?))
+    }
+}
+
 impl<'a> PrefabData<'a> for Transform {
     type SystemData = WriteStorage<'a, Transform>;
     type Result = ();
@@ -61,6 +74,16 @@ impl<'a> PrefabData<'a> for Transform {
     }
 }
 
+impl<'a> PrefabExtract<'a> for Transform {
+    fn extract(
+        entity: Entity,
+        storages: &mut Self::SystemData,
+        _: &[Entity],
+    ) -> Result<Option<Self>, Error> {
+        Ok(storages.get(entity).cloned())
+    }
+}
+
 impl<'a> PrefabData<'a> for Named {
     type SystemData = (WriteStorage<'a, Named>,);
     type Result = ();
@@ -77,6 +100,16 @@ impl<'a> PrefabData<'a> for Named {
     }
 }
 
+impl<'a> PrefabExtract<'a> for Named {
+    fn extract(
+        entity: Entity,
+        storages: &mut Self::SystemData,
+        _: &[Entity],
+    ) -> Result<Option<Self>, Error> {
+        Ok(storages.0.get(entity).cloned())
+    }
+}
+
 macro_rules! impl_data {
     ( $($ty:ident:$i:tt),* ) => {
         #[allow(unused)]
@@ -116,6 +149,27 @@ macro_rules! impl_data {
                 Ok(ret)
             }
         }
+
+        #[allow(unused)]
+        impl<'a, $($ty),*> PrefabExtract<'a> for ( $( $ty , )* )
+            where $( $ty : PrefabExtract<'a> ),*
+        {
+            fn extract(
+                entity: Entity,
+                system_data: &mut Self::SystemData,
+                entities: &[Entity],
+            ) -> Result<Option<Self>, Error> {
+                #![allow(unused_variables)]
+                Ok(Some((
+                    $(
+                        match $ty::extract(entity, &mut system_data.$i, entities)? {
+                            Some(data) => data,
+                            None => return Ok(None),
+                        },
+                    )*
+                )))
+            }
+        }
     };
 }
 
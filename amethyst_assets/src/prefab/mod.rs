@@ -1,20 +1,30 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
+use derivative::Derivative;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
-use amethyst_core::ecs::prelude::{
-    Component, DenseVecStorage, Entity, FlaggedStorage, Read, ReadExpect, ResourceId, SystemData,
-    World, WriteStorage,
+use amethyst_core::{
+    ecs::prelude::{
+        BitSet, Component, DenseVecStorage, Entities, Entity, FlaggedStorage, Join, Read,
+        ReadExpect, ReadStorage, ResourceId, SystemData, World, WriteStorage,
+    },
+    Parent,
 };
-use amethyst_error::Error;
+use amethyst_error::{format_err, Error};
 
 use crate::{
     Asset, AssetStorage, Format, Handle, Loader, Progress, ProgressCounter, SerializableFormat,
+    Tracker,
 };
 
-pub use self::system::{PrefabLoaderSystem, PrefabLoaderSystemDesc};
+pub use self::{
+    spawn::PrefabOverrides,
+    system::{PrefabLoaderSystem, PrefabLoaderSystemDesc},
+};
 
 mod impls;
+mod spawn;
 mod system;
 
 /// Trait for loading a prefabs data for a single entity
@@ -73,6 +83,33 @@ pub trait PrefabData<'a> {
     }
 }
 
+/// Trait for capturing the prefab data of a single entity from the world, the reverse of
+/// `PrefabData::add_to_entity`.
+///
+/// Used by `Prefab::extract` to save entities built at runtime back to a prefab. Can be
+/// derived together with `PrefabData` by adding `#[prefab(Extract)]` to the type.
+pub trait PrefabExtract<'a>: PrefabData<'a> + Sized {
+    /// Extract the data of the given `Entity`
+    ///
+    /// ### Parameters:
+    ///
+    /// - `entity`: `Entity` to extract the data from
+    /// - `system_data`: `SystemData` for the prefab
+    /// - `entities`: the entities being extracted, in the order of the prefab entities, so
+    ///               references to other entities can be stored as indices
+    ///
+    /// ### Returns
+    ///
+    /// - `Err(error)` - if an `Error` occurs
+    /// - `Ok(None)` - if the entity doesn't have the data
+    /// - `Ok(Some(data))` - the extracted data
+    fn extract(
+        entity: Entity,
+        system_data: &mut Self::SystemData,
+        entities: &[Entity],
+    ) -> Result<Option<Self>, Error>;
+}
+
 /// Main `Prefab` structure, containing all data loaded in a single prefab.
 ///
 /// Contains a list prefab data for the entities affected by the prefab. The first entry in the
@@ -101,6 +138,30 @@ pub trait PrefabData<'a> {
 /// }
 /// ```
 ///
+/// ### Inheritance
+///
+/// A prefab can be derived from a base prefab file, which is loaded by the
+/// `PrefabLoaderSystem` with the format given to `PrefabLoaderSystemDesc::with_base_format`.
+/// The entities of the derived prefab are matched with the base entities by index:
+/// their data is added on top of the base data, so components present in both replace
+/// the base components, and a parent index overrides the base parent. Entities beyond
+/// the entities of the base are added. Bases can be derived from other bases again,
+/// but prefabs whose chain of bases loops fail to load.
+///
+/// ```rust,ignore
+/// // goblin_archer.ron
+/// #![enable(implicit_some)]
+/// Prefab(
+///     base: "prefab/goblin.ron",
+///     entities: [
+///         // the main entity of "goblin.ron" is used as is
+///         (),
+///         // a bow for the goblin
+///         (parent: 0, data: (weapon: Bow)),
+///     ],
+/// )
+/// ```
+///
 /// ### Type parameters:
 ///
 /// - `T`: `PrefabData`
@@ -108,6 +169,11 @@ pub trait PrefabData<'a> {
 pub struct Prefab<T> {
     #[serde(skip)]
     tag: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base: Option<String>,
+    #[serde(skip)]
+    base_handle: Option<Handle<Prefab<T>>>,
+    #[serde(default)]
     entities: Vec<PrefabEntity<T>>,
     #[serde(skip)]
     counter: Option<ProgressCounter>,
@@ -137,6 +203,11 @@ impl<T> PrefabEntity<T> {
         PrefabEntity { parent, data }
     }
 
+    /// Get parent index
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
     /// Set parent index
     pub fn set_parent(&mut self, parent: usize) {
         self.parent = Some(parent);
@@ -196,6 +267,8 @@ impl<T> Prefab<T> {
     pub fn new() -> Self {
         Prefab {
             tag: None,
+            base: None,
+            base_handle: None,
             entities: vec![PrefabEntity::default()],
             counter: None,
         }
@@ -205,13 +278,93 @@ impl<T> Prefab<T> {
     pub fn new_main(data: T) -> Self {
         Prefab {
             tag: None,
+            base: None,
+            base_handle: None,
             entities: vec![PrefabEntity::new(None, Some(data))],
             counter: None,
         }
     }
 
+    /// Create an empty prefab derived from the base prefab with the given name.
+    ///
+    /// See the [inheritance](#inheritance) section.
+    pub fn derived<N: Into<String>>(base: N) -> Self {
+        Prefab {
+            tag: None,
+            base: Some(base.into()),
+            base_handle: None,
+            entities: Vec::new(),
+            counter: None,
+        }
+    }
+
+    /// Get the name of the base prefab, if this prefab is derived from one.
+    pub fn base(&self) -> Option<&str> {
+        self.base.as_ref().map(String::as_str)
+    }
+
+    /// Set the name of the base prefab.
+    pub fn set_base(&mut self, base: Option<String>) {
+        self.base = base;
+        self.base_handle = None;
+    }
+
+    /// Capture `root` and its descendants into a new prefab.
+    ///
+    /// `root` becomes the main entity, and its descendants are found through their `Parent`
+    /// components, which are stored as parent indices in the prefab. The data of each entity
+    /// is extracted with `PrefabExtract`, so the prefab can be written with a format like
+    /// RON and loaded again later.
+    ///
+    /// ```rust,ignore
+    /// let prefab = Prefab::<MyPrefabData>::extract(&world, level_root)?;
+    /// let ron = ron::ser::to_string_pretty(&prefab, Default::default())?;
+    /// ```
+    pub fn extract<'a>(world: &'a World, root: Entity) -> Result<Self, Error>
+    where
+        T: PrefabExtract<'a>,
+    {
+        let (entities, parents, mut system_data) =
+            <(Entities<'a>, ReadStorage<'a, Parent>, T::SystemData)>::fetch(world);
+
+        let mut children = std::collections::HashMap::<Entity, Vec<Entity>>::new();
+        for (entity, parent) in (&*entities, &parents).join() {
+            children.entry(parent.entity).or_default().push(entity);
+        }
+        let mut hierarchy = vec![root];
+        let mut parent_indices = vec![None];
+        let mut visited = BitSet::new();
+        visited.add(root.id());
+        let mut index = 0;
+        while index < hierarchy.len() {
+            for &child in children.get(&hierarchy[index]).into_iter().flatten() {
+                if !visited.add(child.id()) {
+                    hierarchy.push(child);
+                    parent_indices.push(Some(index));
+                }
+            }
+            index += 1;
+        }
+
+        let mut prefab = Prefab {
+            tag: None,
+            base: None,
+            base_handle: None,
+            entities: Vec::with_capacity(hierarchy.len()),
+            counter: None,
+        };
+        for (&entity, parent) in hierarchy.iter().zip(parent_indices) {
+            let data = T::extract(entity, &mut system_data, &hierarchy)?;
+            prefab.entities.push(PrefabEntity::new(parent, data));
+        }
+        Ok(prefab)
+    }
+
     /// Set main `Entity` data
     pub fn main(&mut self, data: Option<T>) {
+        if self.entities.is_empty() {
+            self.entities.push(PrefabEntity::default());
+        }
         self.entities[0].data = data;
     }
 
@@ -420,6 +573,138 @@ where
     }
 }
 
+impl<'a, A, F> PrefabExtract<'a> for AssetPrefab<A, F>
+where
+    A: Asset,
+    F: Format<A::Data>,
+{
+    /// Extracts a reference to the file the asset was loaded from with a format of type `F`,
+    /// or the handle itself if it wasn't loaded that way.
+    fn extract(
+        entity: Entity,
+        (_, handles, storage): &mut Self::SystemData,
+        _: &[Entity],
+    ) -> Result<Option<Self>, Error> {
+        Ok(handles
+            .get(entity)
+            .map(|handle| match storage.file_origin::<F>(handle) {
+                Some((name, format)) => AssetPrefab::File(name, format),
+                None => AssetPrefab::Handle(handle.clone()),
+            }))
+    }
+}
+
+/// `PrefabData` for instantiating another prefab as a child subtree of the entity.
+///
+/// The nested prefab is loaded by the `PrefabLoaderSystem` while loading the sub assets of
+/// the outer prefab, so the outer prefab finishes loading only after the nested prefab and
+/// its sub assets are loaded. When the outer prefab is instantiated, a new child `Entity`
+/// is created with a `Parent` pointing to the entity, and the nested prefab is instantiated
+/// with that child as its main entity.
+///
+/// A prefab must not contain itself, neither directly nor through other nested prefabs.
+///
+/// ### Type parameters:
+///
+/// - `T`: `PrefabData` of the nested prefab
+/// - `F`: `Format` for loading the nested prefab
+#[derive(Derivative, Deserialize, Serialize)]
+#[derivative(Clone(bound = "F: Clone"), Debug(bound = "F: std::fmt::Debug"))]
+pub struct PrefabInstance<T, F> {
+    name: String,
+    format: F,
+    #[serde(skip)]
+    #[derivative(Debug = "ignore")]
+    handle: NestedHandle<T>,
+    #[serde(skip)]
+    #[derivative(Debug = "ignore")]
+    _m: PhantomData<T>,
+}
+
+/// Handle of a nested prefab, set by the `PrefabLoaderSystem` once the prefab is loaded.
+pub(crate) type NestedHandle<T> = Arc<Mutex<Option<Handle<Prefab<T>>>>>;
+
+impl<T, F> PrefabInstance<T, F> {
+    /// Create a nested prefab instance, loaded from the file with the given name.
+    pub fn new<N: Into<String>>(name: N, format: F) -> Self {
+        PrefabInstance {
+            name: name.into(),
+            format,
+            handle: NestedHandle::default(),
+            _m: PhantomData,
+        }
+    }
+
+    /// Get the name of the nested prefab.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<'a, T, F> PrefabData<'a> for PrefabInstance<T, F>
+where
+    T: Send + Sync + 'static,
+    F: Format<Prefab<T>> + Clone,
+{
+    type SystemData = Read<'a, PrefabInstances<T>>;
+    type Result = ();
+
+    fn add_to_entity(
+        &self,
+        entity: Entity,
+        instances: &mut Self::SystemData,
+        _: &[Entity],
+        _: &[Entity],
+    ) -> Result<(), Error> {
+        let handle = self.handle.lock().clone().ok_or_else(|| {
+            format_err!(
+                "Nested prefab {:?} was instantiated before loading",
+                self.name
+            )
+        })?;
+        instances.spawns.lock().push((entity, handle));
+        Ok(())
+    }
+
+    fn load_sub_assets(
+        &mut self,
+        mut progress: &mut ProgressCounter,
+        instances: &mut Self::SystemData,
+    ) -> Result<bool, Error> {
+        progress.add_assets(1);
+        let format: Box<dyn Format<Prefab<T>>> = Box::new(self.format.clone());
+        let tracker: Box<dyn Tracker> = Box::new(progress.create_tracker());
+        instances
+            .requests
+            .lock()
+            .push((self.name.clone(), format, self.handle.clone(), tracker));
+        Ok(true)
+    }
+}
+
+/// Nested prefab instances requested by `PrefabInstance`, resolved by the `PrefabLoaderSystem`.
+///
+/// The handles of loaded nested prefabs are kept by the `PrefabInstance`s, so nested prefabs
+/// are unloaded together with the prefabs containing them.
+///
+/// ### Type parameters:
+///
+/// - `T`: `PrefabData`
+#[derive(Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct PrefabInstances<T> {
+    #[allow(clippy::type_complexity)]
+    pub(crate) requests: Mutex<
+        Vec<(
+            String,
+            Box<dyn Format<Prefab<T>>>,
+            NestedHandle<T>,
+            Box<dyn Tracker>,
+        )>,
+    >,
+    pub(crate) spawns: Mutex<Vec<(Entity, Handle<Prefab<T>>)>>,
+}
+
 /// Helper structure for loading prefabs.
 ///
 /// The recommended way of using this from `State`s is to use `world.exec`.
@@ -470,11 +755,11 @@ mod tests {
     use rayon::ThreadPoolBuilder;
 
     use amethyst_core::{
-        ecs::{Builder, RunNow, World, WorldExt},
-        SystemDesc, Time, Transform,
+        ecs::{Builder, Join, RunNow, World, WorldExt},
+        Named, Parent, SystemDesc, Time, Transform,
     };
 
-    use crate::Loader;
+    use crate::{test_util::run_until, Completion, Loader, RonFormat, Source};
 
     use super::*;
 
@@ -505,4 +790,277 @@ mod tests {
         );
         assert!(world.read_storage::<Transform>().get(root_entity).is_some());
     }
+
+    /// Source serving a base prefab with a named main entity and a named child.
+    struct BaseSource;
+
+    impl Source for BaseSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            Ok(br#"Prefab(
+                entities: [
+                    (data: (None, Some((name: "goblin")))),
+                    (parent: Some(0), data: (None, Some((name: "club")))),
+                ],
+            )"#
+            .to_vec())
+        }
+    }
+
+    #[test]
+    fn derived_prefab_is_added_on_top_of_base() {
+        type Data = (Option<Transform>, Option<Named>);
+
+        let mut world = World::new();
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        world.insert(pool.clone());
+        world.insert(Loader::with_default_source(BaseSource, pool));
+        world.insert(Time::default());
+        let mut system = PrefabLoaderSystemDesc::<Data>::default()
+            .with_base_format(RonFormat)
+            .build(&mut world);
+        RunNow::setup(&mut system, &mut world);
+
+        let mut prefab = Prefab::derived("goblin.ron");
+        prefab.main(Some((Some(Transform::default()), None)));
+        prefab.add(None, Some((None, Some(Named::new("bow")))));
+        prefab.add(Some(0), Some((None, Some(Named::new("quiver")))));
+
+        let handle = world.read_resource::<Loader>().load_from_data(
+            prefab,
+            (),
+            &world.read_resource::<AssetStorage<Prefab<Data>>>(),
+        );
+        let root_entity = world.create_entity().with(handle).build();
+        run_until(0, |_| {
+            system.run_now(&world);
+            world.read_storage::<Transform>().contains(root_entity)
+        });
+
+        let named = world.read_storage::<Named>();
+        assert_eq!("goblin", named.get(root_entity).unwrap().name);
+        let mut names: Vec<_> = (&named).join().map(|n| n.name.to_string()).collect();
+        names.sort();
+        assert_eq!(vec!["bow", "goblin", "quiver"], names);
+        // the parent of the overridden entity is inherited from the base
+        assert_eq!(2, world.read_storage::<Parent>().join().count());
+    }
+
+    /// Source serving prefabs derived from each other in a loop.
+    struct LoopSource;
+
+    impl Source for LoopSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+            let base = if path == "a.ron" { "b.ron" } else { "a.ron" };
+            Ok(format!("Prefab(base: {:?})", base).into_bytes())
+        }
+    }
+
+    #[test]
+    fn base_cycles_fail_to_load() {
+        type Data = (Option<Transform>, Option<Named>);
+
+        let mut world = World::new();
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        world.insert(pool.clone());
+        world.insert(Loader::with_default_source(LoopSource, pool));
+        world.insert(Time::default());
+        let mut system = PrefabLoaderSystemDesc::<Data>::default()
+            .with_base_format(RonFormat)
+            .build(&mut world);
+        RunNow::setup(&mut system, &mut world);
+
+        let mut progress = ProgressCounter::new();
+        world.read_resource::<Loader>().load_from_data(
+            Prefab::<Data>::derived("a.ron"),
+            &mut progress,
+            &world.read_resource::<AssetStorage<Prefab<Data>>>(),
+        );
+        run_until(0, |_| {
+            system.run_now(&world);
+            progress.complete() != Completion::Loading
+        });
+        assert_eq!(Completion::Failed, progress.complete());
+    }
+
+    #[test]
+    fn spawn_instantiates_loaded_prefab_with_overrides() {
+        type Data = (Option<Transform>, Option<Named>);
+
+        let mut world = World::new();
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        world.insert(pool.clone());
+        world.insert(Loader::with_default_source(BaseSource, pool));
+        world.insert(Time::default());
+        let mut system = PrefabLoaderSystemDesc::<Data>::default().build(&mut world);
+        RunNow::setup(&mut system, &mut world);
+
+        let handle = world.read_resource::<Loader>().load(
+            "goblin.ron",
+            RonFormat,
+            (),
+            &world.read_resource::<AssetStorage<Prefab<Data>>>(),
+        );
+        run_until(0, |_| {
+            system.run_now(&world);
+            world
+                .read_resource::<AssetStorage<Prefab<Data>>>()
+                .contains(&handle)
+        });
+
+        let parent = world.create_entity().build();
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(1.0, 2.0, 3.0);
+        let overrides = PrefabOverrides::new()
+            .with_parent(parent)
+            .with_transform(transform)
+            .with_data(1, (None, Some(Named::new("sword"))));
+        let entities = Prefab::spawn(&world, &handle, overrides).unwrap();
+
+        assert_eq!(2, entities.len());
+        let named = world.read_storage::<Named>();
+        assert_eq!("goblin", named.get(entities[0]).unwrap().name);
+        assert_eq!("sword", named.get(entities[1]).unwrap().name);
+        let parents = world.read_storage::<Parent>();
+        assert_eq!(parent, parents.get(entities[0]).unwrap().entity);
+        assert_eq!(entities[0], parents.get(entities[1]).unwrap().entity);
+        let transforms = world.read_storage::<Transform>();
+        let x = transforms.get(entities[0]).unwrap().translation().x;
+        assert!((x - 1.0).abs() < std::f32::EPSILON);
+    }
+
+    #[test]
+    fn asset_prefabs_are_extracted_as_file_references() {
+        type Goblin = Prefab<(Option<Transform>, Option<Named>)>;
+
+        let mut world = World::new();
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        world.insert(pool.clone());
+        world.insert(Loader::with_default_source(BaseSource, pool));
+        world.insert(AssetStorage::<Goblin>::new());
+        world.register::<Handle<Goblin>>();
+        world.register::<Parent>();
+
+        let handle = world.read_resource::<Loader>().load(
+            "goblin.ron",
+            RonFormat,
+            (),
+            &world.read_resource::<AssetStorage<Goblin>>(),
+        );
+        let entity = world.create_entity().with(handle).build();
+
+        let prefab = Prefab::<AssetPrefab<Goblin, RonFormat>>::extract(&world, entity).unwrap();
+        match prefab.entities().next().and_then(PrefabEntity::data) {
+            Some(AssetPrefab::File(name, _)) => assert_eq!("goblin.ron", name),
+            _ => panic!("Expected a file reference"),
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Room(Option<Named>, Option<PrefabInstance<Room, RonFormat>>);
+
+    impl<'a> PrefabData<'a> for Room {
+        type SystemData = (
+            <Named as PrefabData<'a>>::SystemData,
+            <PrefabInstance<Room, RonFormat> as PrefabData<'a>>::SystemData,
+        );
+        type Result = ();
+
+        fn add_to_entity(
+            &self,
+            entity: Entity,
+            system_data: &mut Self::SystemData,
+            entities: &[Entity],
+            children: &[Entity],
+        ) -> Result<(), Error> {
+            self.0
+                .add_to_entity(entity, &mut system_data.0, entities, children)?;
+            self.1
+                .add_to_entity(entity, &mut system_data.1, entities, children)?;
+            Ok(())
+        }
+
+        fn load_sub_assets(
+            &mut self,
+            progress: &mut ProgressCounter,
+            system_data: &mut Self::SystemData,
+        ) -> Result<bool, Error> {
+            self.1.load_sub_assets(progress, &mut system_data.1)
+        }
+    }
+
+    /// Source serving a prefab with a named main entity, nesting a prefab for each shelf.
+    struct FurnitureSource;
+
+    impl Source for FurnitureSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+            Ok(match path {
+                "shelf.ron" => br#"Prefab(
+                    entities: [
+                        (data: (Some((name: "shelf")), Some((name: "book.ron", format: ())))),
+                    ],
+                )"#
+                .to_vec(),
+                _ => br#"Prefab(entities: [(data: (Some((name: "book")), None))])"#.to_vec(),
+            })
+        }
+    }
+
+    #[test]
+    fn nested_prefabs_are_instantiated_as_children() {
+        let mut world = World::new();
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        world.insert(pool.clone());
+        world.insert(Loader::with_default_source(FurnitureSource, pool));
+        world.insert(Time::default());
+        let mut system = PrefabLoaderSystemDesc::<Room>::default().build(&mut world);
+        RunNow::setup(&mut system, &mut world);
+
+        let mut prefab = Prefab::new_main(Room(Some(Named::new("room")), None));
+        prefab.add(
+            Some(0),
+            Some(Room(
+                None,
+                Some(PrefabInstance::new("shelf.ron", RonFormat)),
+            )),
+        );
+
+        let mut progress = ProgressCounter::new();
+        let handle = world.read_resource::<Loader>().load_from_data(
+            prefab,
+            &mut progress,
+            &world.read_resource::<AssetStorage<Prefab<Room>>>(),
+        );
+        let root_entity = world.create_entity().with(handle).build();
+        run_until(0, |_| {
+            system.run_now(&world);
+            world.read_storage::<Named>().join().count() == 3
+        });
+        // the room only finished loading with the shelf and its book
+        assert!(progress.is_complete());
+
+        let named = world.read_storage::<Named>();
+        let parents = world.read_storage::<Parent>();
+        let parent_name = |name: &str| {
+            let (_, parent) = (&named, &parents)
+                .join()
+                .find(|(n, _)| n.name == name)
+                .unwrap();
+            named.get(parent.entity).map(|n| n.name.to_string())
+        };
+        assert_eq!("room", named.get(root_entity).unwrap().name);
+        assert_eq!(None, parent_name("shelf"));
+        assert_eq!(Some("shelf".to_string()), parent_name("book"));
+    }
 }
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use derivative::Derivative;
+
+use amethyst_core::{
+    ecs::{world::EntitiesRes, Entities, Entity, Read, SystemData, World, WriteStorage},
+    Parent, Transform,
+};
+use amethyst_error::{format_err, Error};
+
+use crate::{AssetStorage, Handle};
+
+use super::{Prefab, PrefabData, PrefabTag};
+
+/// Per-instance changes to a prefab spawned with `Prefab::spawn`.
+///
+/// ### Type parameters:
+///
+/// - `T`: `PrefabData`
+#[derive(Derivative)]
+#[derivative(Default(bound = ""), Debug(bound = "T: std::fmt::Debug"))]
+pub struct PrefabOverrides<T> {
+    data: Vec<(usize, T)>,
+    parent: Option<Entity>,
+    transform: Option<Transform>,
+}
+
+impl<T> PrefabOverrides<T> {
+    /// Create overrides which don't change anything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `data` for the prefab entity with the given index, instead of the data of the prefab.
+    pub fn with_data(mut self, index: usize, data: T) -> Self {
+        self.data.retain(|(i, _)| *i != index);
+        self.data.push((index, data));
+        self
+    }
+
+    /// Make the main entity a child of `parent`.
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Insert `transform` on the main entity, replacing any `Transform` from the prefab.
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = Some(transform);
+        self
+    }
+}
+
+impl<T> Prefab<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Instantiate the loaded prefab behind `handle` right away, instead of attaching the handle
+    /// to an entity and waiting for the `PrefabLoaderSystem`.
+    ///
+    /// Returns the created entities in the order of the prefab entities, so the first one is
+    /// the main entity. Nested `PrefabInstance`s are still instantiated by the
+    /// `PrefabLoaderSystem`.
+    ///
+    /// ```rust,ignore
+    /// let overrides = PrefabOverrides::new().with_transform(muzzle_transform);
+    /// let entities = Prefab::spawn(&world, &projectile_prefab, overrides)?;
+    /// ```
+    ///
+    /// From a system, this can be deferred with `LazyUpdate::exec_mut`.
+    ///
+    /// ### Errors
+    ///
+    /// If the prefab or one of its bases isn't loaded yet.
+    pub fn spawn<'a>(
+        world: &'a World,
+        handle: &Handle<Prefab<T>>,
+        overrides: PrefabOverrides<T>,
+    ) -> Result<Vec<Entity>, Error>
+    where
+        T: PrefabData<'a>,
+    {
+        let mut created = Vec::new();
+        {
+            let (entities, storage, mut parents, mut tags, mut system_data) =
+                <(
+                    Entities<'a>,
+                    Read<'a, AssetStorage<Prefab<T>>>,
+                    WriteStorage<'a, Parent>,
+                    WriteStorage<'a, PrefabTag<T>>,
+                    T::SystemData,
+                )>::fetch(world);
+
+            let chain = storage
+                .get(handle)
+                .ok_or_else(|| format_err!("Prefab is not loaded"))?
+                .with_bases(&storage)
+                .ok_or_else(|| format_err!("Bases of the prefab are not loaded"))?;
+            let root = entities.create();
+            if let Some(parent) = overrides.parent {
+                parents.insert(root, Parent { entity: parent })?;
+            }
+            instantiate(
+                &chain,
+                root,
+                &entities,
+                &mut parents,
+                &mut tags,
+                &mut system_data,
+                &overrides.data,
+                &mut created,
+            )?;
+        }
+        if let Some(transform) = overrides.transform {
+            WriteStorage::<Transform>::fetch(world).insert(created[0], transform)?;
+        }
+
+        Ok(created)
+    }
+
+    /// Get the prefab preceded by its bases, starting with the first base, or `None` if a base
+    /// isn't loaded yet.
+    pub(crate) fn with_bases<'p>(
+        &'p self,
+        storage: &'p AssetStorage<Prefab<T>>,
+    ) -> Option<Vec<&'p Prefab<T>>> {
+        let mut chain = vec![self];
+        let mut next = self.base_handle.as_ref();
+        while let Some(base_handle) = next {
+            let base = storage.get(base_handle)?;
+            chain.push(base);
+            next = base.base_handle.as_ref();
+        }
+        chain.reverse();
+        Some(chain)
+    }
+}
+
+/// Create the entities of a prefab preceded by its bases, with `root` as the main entity.
+///
+/// The created entities are written to `created` in the order of the prefab entities. The
+/// `replaced` data is used instead of the prefab data of the entities with the given indices.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn instantiate<'a, T>(
+    chain: &[&Prefab<T>],
+    root: Entity,
+    entities: &EntitiesRes,
+    parents: &mut WriteStorage<'a, Parent>,
+    tags: &mut WriteStorage<'a, PrefabTag<T>>,
+    system_data: &mut T::SystemData,
+    replaced: &[(usize, T)],
+    created: &mut Vec<Entity>,
+) -> Result<(), Error>
+where
+    T: PrefabData<'a> + Send + Sync + 'static,
+{
+    let tag = chain.last().and_then(|prefab| prefab.tag);
+    created.clear();
+    created.push(root);
+
+    // create entities
+    let num_entities = chain.iter().map(|p| p.entities.len()).max().unwrap_or(0);
+    let mut children = HashMap::new();
+    for index in 1..num_entities {
+        let new_entity = entities.create();
+        created.push(new_entity);
+        // derived prefabs override the parent of their base
+        let parent = chain
+            .iter()
+            .rev()
+            .filter_map(|p| p.entities.get(index).and_then(|e| e.parent))
+            .next();
+        if let Some(parent) = parent {
+            parents.insert(
+                new_entity,
+                Parent {
+                    entity: created[parent],
+                },
+            )?;
+            children
+                .entry(parent)
+                .or_insert_with(Vec::new)
+                .push(new_entity);
+        }
+        if let Some(tag) = tag {
+            tags.insert(new_entity, PrefabTag::new(tag))?;
+        }
+    }
+
+    // create components, bases first so derived data replaces it
+    let children_of = |index: usize| children.get(&index).map(Vec::as_slice).unwrap_or(&[]);
+    for prefab in chain {
+        for (index, entity_data) in prefab.entities.iter().enumerate() {
+            if replaced.iter().any(|(i, _)| *i == index) {
+                continue;
+            }
+            if let Some(ref prefab_data) = entity_data.data {
+                prefab_data.add_to_entity(
+                    created[index],
+                    system_data,
+                    created,
+                    children_of(index),
+                )?;
+            }
+        }
+    }
+    for (index, data) in replaced {
+        let entity = *created
+            .get(*index)
+            .ok_or_else(|| format_err!("Prefab has no entity with index {}", index))?;
+        data.add_to_entity(entity, system_data, created, children_of(*index))?;
+    }
+
+    Ok(())
+}
@@ -1,12 +1,17 @@
-use std::{collections::HashMap, marker::PhantomData};
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    sync::Arc,
+};
 
 use derivative::Derivative;
 use log::error;
+use parking_lot::Mutex;
 
 use amethyst_core::{
     ecs::{
-        storage::ComponentEvent, BitSet, Entities, Entity, Join, Read, ReadExpect, ReadStorage,
-        ReaderId, System, SystemData, World, Write, WriteStorage,
+        storage::ComponentEvent, BitSet, Entities, Entity, Join, Read, ReadExpect, ReaderId,
+        System, SystemData, World, Write, WriteStorage,
     },
     ArcThreadPool, Parent, SystemDesc, Time,
 };
@@ -15,17 +20,35 @@ use amethyst_error::{format_err, Error, ResultExt};
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
-use crate::{AssetStorage, Completion, Handle, HotReloadStrategy, ProcessingState};
+use crate::{
+    Asset, AssetStats, AssetStorage, Completion, Format, FormatValue, Handle, HotReloadStrategy,
+    Loader, ProcessingState, ProgressCounter, Source, Tracker,
+};
 
-use super::{Prefab, PrefabData, PrefabTag};
+use super::{spawn::instantiate, NestedHandle, Prefab, PrefabData, PrefabInstances, PrefabTag};
 
 /// Builds a `PrefabLoaderSystem`.
 #[derive(Derivative, Debug)]
 #[derivative(Default(bound = ""))]
 pub struct PrefabLoaderSystemDesc<T> {
+    base_format: Option<Box<dyn Format<Prefab<T>>>>,
     marker: PhantomData<T>,
 }
 
+impl<T> PrefabLoaderSystemDesc<T> {
+    /// Sets the format used to load the base prefabs of derived prefabs.
+    ///
+    /// Without a base format, derived prefabs fail to load.
+    pub fn with_base_format<F>(mut self, format: F) -> Self
+    where
+        F: Format<Prefab<T>>,
+        T: Send + Sync + 'static,
+    {
+        self.base_format = Some(Box::new(format));
+        self
+    }
+}
+
 impl<'a, 'b, T> SystemDesc<'a, 'b, PrefabLoaderSystem<T>> for PrefabLoaderSystemDesc<T>
 where
     T: PrefabData<'a> + Send + Sync + 'static,
@@ -35,7 +58,9 @@ where
 
         let insert_reader = WriteStorage::<Handle<Prefab<T>>>::fetch(&world).register_reader();
 
-        PrefabLoaderSystem::new(insert_reader)
+        let mut system = PrefabLoaderSystem::new(insert_reader);
+        system.base_format = self.base_format;
+        system
     }
 }
 
@@ -46,6 +71,13 @@ where
 /// - `T`: `PrefabData`
 pub struct PrefabLoaderSystem<T> {
     _m: PhantomData<T>,
+    base_format: Option<Box<dyn Format<Prefab<T>>>>,
+    bases: HashMap<String, (Handle<Prefab<T>>, ProgressCounter)>,
+    base_of: Arc<Mutex<HashMap<String, Option<String>>>>,
+    requested_bases: Vec<String>,
+    waiting_bases: HashSet<String>,
+    instances: HashMap<String, (Handle<Prefab<T>>, ProgressCounter)>,
+    pending_instances: Vec<(String, NestedHandle<T>, Box<dyn Tracker>)>,
     entities: Vec<Entity>,
     finished: Vec<Entity>,
     to_process: BitSet,
@@ -61,6 +93,13 @@ where
     pub fn new(insert_reader: ReaderId<ComponentEvent>) -> Self {
         Self {
             _m: PhantomData,
+            base_format: None,
+            bases: HashMap::default(),
+            base_of: Arc::default(),
+            requested_bases: Vec::default(),
+            waiting_bases: HashSet::default(),
+            instances: HashMap::default(),
+            pending_instances: Vec::default(),
             entities: Vec::default(),
             finished: Vec::default(),
             to_process: BitSet::default(),
@@ -77,11 +116,14 @@ where
     #[allow(clippy::type_complexity)]
     type SystemData = (
         Entities<'a>,
+        ReadExpect<'a, Loader>,
         Write<'a, AssetStorage<Prefab<T>>>,
-        ReadStorage<'a, Handle<Prefab<T>>>,
+        WriteStorage<'a, Handle<Prefab<T>>>,
+        Read<'a, PrefabInstances<T>>,
         Read<'a, Time>,
         ReadExpect<'a, ArcThreadPool>,
         Option<Read<'a, HotReloadStrategy>>,
+        Read<'a, AssetStats>,
         WriteStorage<'a, Parent>,
         WriteStorage<'a, PrefabTag<T>>,
         T::SystemData,
@@ -93,11 +135,14 @@ where
 
         let (
             entities,
+            loader,
             mut prefab_storage,
-            prefab_handles,
+            mut prefab_handles,
+            nested,
             time,
             pool,
             strategy,
+            stats,
             mut parents,
             mut tags,
             mut prefab_system_data,
@@ -107,6 +152,42 @@ where
             |mut d| {
                 d.tag = Some(self.next_tag);
                 self.next_tag += 1;
+                let unresolved_base = if d.base_handle.is_none() {
+                    d.base.as_ref()
+                } else {
+                    None
+                };
+                if let Some(base) = unresolved_base {
+                    if self.base_format.is_none() {
+                        return Err(format_err!(
+                            "Prefab is derived from {:?}, but no base format is set. \
+                             See `PrefabLoaderSystemDesc::with_base_format`.",
+                            base
+                        ));
+                    }
+                    if let Some(chain) = base_cycle(&self.base_of.lock(), base) {
+                        return Err(format_err!(
+                            "Base prefabs are derived from each other: {}",
+                            chain.join(" -> ")
+                        ));
+                    }
+                    match self.bases.get(base) {
+                        Some((handle, progress)) => match progress.complete() {
+                            Completion::Complete => d.base_handle = Some(handle.clone()),
+                            Completion::Failed => {
+                                return Err(format_err!("Failed loading base prefab {:?}", base));
+                            }
+                            Completion::Loading => {
+                                self.waiting_bases.insert(base.clone());
+                                return Ok(ProcessingState::Loading(d));
+                            }
+                        },
+                        None => {
+                            self.requested_bases.push(base.clone());
+                            return Ok(ProcessingState::Loading(d));
+                        }
+                    }
+                }
                 if !d.loading()
                     && !d
                         .load_sub_assets(&mut prefab_system_data)
@@ -127,6 +208,65 @@ where
             &**pool,
             strategy,
         );
+        stats.update(&*prefab_storage);
+        // derived prefabs keep their base alive once they got its handle, so only the bases
+        // still waited for are kept here
+        let waiting_bases = &self.waiting_bases;
+        self.bases.retain(|base, _| waiting_bases.contains(base));
+        self.waiting_bases.clear();
+        for base in self.requested_bases.drain(..) {
+            if self.bases.contains_key(&base) {
+                continue;
+            }
+            let format = BaseFormat {
+                name: base.clone(),
+                format: self
+                    .base_format
+                    .clone()
+                    .expect("Unreachable: Bases are only requested with a base format"),
+                base_of: self.base_of.clone(),
+            };
+            let mut progress = ProgressCounter::new();
+            let handle = loader.load(base.clone(), format, &mut progress, &*prefab_storage);
+            self.bases.insert(base, (handle, progress));
+        }
+        let bases = &self.bases;
+        self.base_of
+            .lock()
+            .retain(|base, _| bases.contains_key(base));
+        let requests = nested.requests.lock().drain(..).collect::<Vec<_>>();
+        for (name, format, slot, tracker) in requests {
+            if !self.instances.contains_key(&name) {
+                let mut progress = ProgressCounter::new();
+                let handle = loader.load(name.clone(), format, &mut progress, &*prefab_storage);
+                self.instances.insert(name.clone(), (handle, progress));
+            }
+            self.pending_instances.push((name, slot, tracker));
+        }
+        // report finished nested prefabs to the prefabs containing them, which keep their
+        // handles from then on
+        for (name, slot, tracker) in std::mem::replace(&mut self.pending_instances, Vec::new()) {
+            let (handle, progress) = &self.instances[&name];
+            match progress.complete() {
+                Completion::Complete => {
+                    *slot.lock() = Some(handle.clone());
+                    tracker.success();
+                }
+                Completion::Failed => tracker.fail(
+                    handle.id(),
+                    Prefab::<T>::NAME,
+                    name.clone(),
+                    format_err!("Failed loading nested prefab {:?}", name),
+                ),
+                Completion::Loading => self.pending_instances.push((name, slot, tracker)),
+            }
+        }
+        let pending_instances = &self.pending_instances;
+        self.instances.retain(|name, _| {
+            pending_instances
+                .iter()
+                .any(|(pending, _, _)| pending == name)
+        });
         prefab_handles
             .channel()
             .read(&mut self.insert_reader)
@@ -137,62 +277,89 @@ where
             });
         self.finished.clear();
         for (root_entity, handle, _) in (&*entities, &prefab_handles, &self.to_process).join() {
-            if let Some(prefab) = prefab_storage.get(handle) {
-                self.finished.push(root_entity);
-                // create entities
-                self.entities.clear();
-                self.entities.push(root_entity);
-
-                let mut children = HashMap::new();
-                for entity_data in prefab.entities.iter().skip(1) {
-                    let new_entity = entities.create();
-                    self.entities.push(new_entity);
-                    if let Some(parent) = entity_data.parent {
-                        parents
-                            .insert(
-                                new_entity,
-                                Parent {
-                                    entity: self.entities[parent],
-                                },
-                            )
-                            .expect("Unable to insert `Parent` for prefab");
-
-                        children
-                            .entry(parent)
-                            .or_insert_with(Vec::new)
-                            .push(new_entity);
-                    }
-                    tags.insert(
-                        new_entity,
-                        PrefabTag::new(
-                            prefab.tag.expect(
-                                "Unreachable: Every loaded prefab should have a `PrefabTag`",
-                            ),
-                        ),
-                    )
-                    .expect("Unable to insert `PrefabTag` for prefab entity");
-                }
-                // create components
-                for (index, entity_data) in prefab.entities.iter().enumerate() {
-                    if let Some(ref prefab_data) = &entity_data.data {
-                        prefab_data
-                            .add_to_entity(
-                                self.entities[index],
-                                &mut prefab_system_data,
-                                &self.entities,
-                                children
-                                    .get(&index)
-                                    .map(|children| &children[..])
-                                    .unwrap_or(&[]),
-                            )
-                            .expect("Unable to add prefab system data to entity");
-                    }
-                }
-            }
+            // the prefab can only be instantiated once it and all its bases are loaded
+            let chain = match prefab_storage
+                .get(handle)
+                .and_then(|prefab| prefab.with_bases(&prefab_storage))
+            {
+                Some(chain) => chain,
+                None => continue,
+            };
+            self.finished.push(root_entity);
+            instantiate(
+                &chain,
+                root_entity,
+                &entities,
+                &mut parents,
+                &mut tags,
+                &mut prefab_system_data,
+                &[],
+                &mut self.entities,
+            )
+            .expect("Unable to instantiate prefab");
         }
 
         for entity in &self.finished {
             self.to_process.remove(entity.id());
         }
+
+        let spawns = nested.spawns.lock().drain(..).collect::<Vec<_>>();
+        for (parent, handle) in spawns {
+            let child = entities.create();
+            parents
+                .insert(child, Parent { entity: parent })
+                .expect("Unable to insert `Parent` for nested prefab");
+            prefab_handles
+                .insert(child, handle)
+                .expect("Unable to insert `Handle` for nested prefab");
+        }
+    }
+}
+
+/// Format loading a base prefab, which records the base the prefab is derived from, so cycles
+/// in the chain of bases can be detected.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+struct BaseFormat<T> {
+    name: String,
+    format: Box<dyn Format<Prefab<T>>>,
+    #[derivative(Debug = "ignore")]
+    base_of: Arc<Mutex<HashMap<String, Option<String>>>>,
+}
+
+impl<T> Format<Prefab<T>> for BaseFormat<T>
+where
+    T: Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        self.format.name()
+    }
+
+    fn import(
+        &self,
+        name: String,
+        source: Arc<dyn Source>,
+        create_reload: Option<Box<dyn Format<Prefab<T>>>>,
+    ) -> Result<FormatValue<Prefab<T>>, Error> {
+        let value = self.format.import(name, source, create_reload)?;
+        self.base_of
+            .lock()
+            .insert(self.name.clone(), value.data.base.clone());
+        Ok(value)
+    }
+}
+
+/// Returns the chain of bases starting with `base` if it loops back on itself.
+fn base_cycle(base_of: &HashMap<String, Option<String>>, base: &str) -> Option<Vec<String>> {
+    let mut chain = vec![base.to_string()];
+    let mut current = base;
+    while let Some(Some(next)) = base_of.get(current) {
+        let looped = chain.contains(next);
+        chain.push(next.clone());
+        if looped {
+            return Some(chain);
+        }
+        current = next;
     }
+    None
 }
@@ -1,12 +1,17 @@
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use amethyst_error::Error;
-use log::error;
+use log::{debug, error};
 use parking_lot::Mutex;
 
+use crate::error::ParseError;
+
 /// Completion status, returned by `ProgressCounter::complete`.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Completion {
@@ -43,12 +48,20 @@ impl Progress for () {
 
 /// A progress tracker which is passed to the `Loader`
 /// in order to check how many assets are loaded.
+///
+/// Assets can be tracked in named groups (see `group`), e.g. to show a
+/// progress bar per category on a loading screen. All counts of a
+/// `ProgressCounter` include the assets of its groups.
 #[derive(Default, Debug)]
 pub struct ProgressCounter {
+    bytes: Arc<ByteCounts>,
     errors: Arc<Mutex<Vec<AssetErrorMeta>>>,
+    groups: Vec<(String, ProgressCounter)>,
     num_assets: usize,
+    num_cancelled: Arc<AtomicUsize>,
     num_failed: Arc<AtomicUsize>,
     num_loading: Arc<AtomicUsize>,
+    timings: Option<Arc<Mutex<Vec<AssetTiming>>>>,
 }
 
 impl ProgressCounter {
@@ -57,38 +70,151 @@ impl ProgressCounter {
         Default::default()
     }
 
+    /// Returns the group with the given name, creating it if it doesn't exist yet.
+    ///
+    /// ```
+    /// # use amethyst_assets::ProgressCounter;
+    /// let mut progress = ProgressCounter::new();
+    /// // loader.load("music/theme.ogg", OggFormat, progress.group("audio"), &storage);
+    /// progress.group("audio");
+    /// assert_eq!(Some("audio"), progress.groups().map(|(name, _)| name).next());
+    /// ```
+    pub fn group(&mut self, name: &str) -> &mut ProgressCounter {
+        let index = match self.groups.iter().position(|(n, _)| n == name) {
+            Some(index) => index,
+            None => {
+                let group = ProgressCounter {
+                    timings: self.timings.clone(),
+                    ..Default::default()
+                };
+                self.groups.push((name.to_owned(), group));
+                self.groups.len() - 1
+            }
+        };
+
+        &mut self.groups[index].1
+    }
+
+    /// Returns the group with the given name, if it exists.
+    pub fn get_group(&self, name: &str) -> Option<&ProgressCounter> {
+        self.groups
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, group)| group)
+    }
+
+    /// Returns all groups with their names, in the order they were created.
+    pub fn groups(&self) -> impl Iterator<Item = (&str, &ProgressCounter)> {
+        self.groups
+            .iter()
+            .map(|(name, group)| (name.as_str(), group))
+    }
+
     /// Removes all errors and returns them.
     pub fn errors(&self) -> Vec<AssetErrorMeta> {
-        let mut lock = self.errors.lock();
-        lock.drain(..).collect()
+        let mut errors: Vec<_> = self.errors.lock().drain(..).collect();
+        for (_, group) in &self.groups {
+            errors.extend(group.errors());
+        }
+
+        errors
     }
 
     /// Returns the number of assets this struct is tracking.
     pub fn num_assets(&self) -> usize {
-        self.num_assets
+        self.sum(|c| c.num_assets)
     }
 
     /// Returns the number of assets that have failed.
     pub fn num_failed(&self) -> usize {
-        self.num_failed.load(Ordering::Relaxed)
+        self.sum(|c| c.num_failed.load(Ordering::Relaxed))
+    }
+
+    /// Returns the number of assets whose loading has been cancelled.
+    pub fn num_cancelled(&self) -> usize {
+        self.sum(|c| c.num_cancelled.load(Ordering::Relaxed))
     }
 
     /// Returns the number of assets that are still loading.
     pub fn num_loading(&self) -> usize {
-        self.num_loading.load(Ordering::Relaxed)
+        self.sum(|c| c.num_loading.load(Ordering::Relaxed))
     }
 
     /// Returns the number of assets that have successfully loaded.
     pub fn num_finished(&self) -> usize {
-        self.num_assets - self.num_loading() - self.num_failed()
+        self.num_assets() - self.num_loading() - self.num_failed() - self.num_cancelled()
+    }
+
+    /// Returns the number of bytes read from sources so far.
+    pub fn num_bytes_read(&self) -> usize {
+        self.sum(|c| c.bytes.read.load(Ordering::Relaxed))
+    }
+
+    /// Returns the fraction of the tracked assets that are done (loaded, failed or
+    /// cancelled) between `0.0` and `1.0`, weighted by their size in bytes.
+    ///
+    /// The size of an asset is only known once it has been read from its source;
+    /// assets that haven't been read yet are assumed to have the average size of
+    /// the assets read so far.
+    pub fn weighted_progress(&self) -> f32 {
+        let num_assets = self.num_assets();
+        if num_assets == 0 {
+            return 1.0;
+        }
+        let num_done = num_assets - self.num_loading();
+        let read = self.num_bytes_read();
+        if read == 0 {
+            return num_done as f32 / num_assets as f32;
+        }
+
+        let num_read = self.sum(|c| c.bytes.num_read.load(Ordering::Relaxed));
+        let finished = self.sum(|c| c.bytes.finished.load(Ordering::Relaxed));
+        let num_read_finished = self.sum(|c| c.bytes.num_finished.load(Ordering::Relaxed));
+        let average = read as f32 / num_read as f32;
+
+        let done = finished as f32 + num_done.saturating_sub(num_read_finished) as f32 * average;
+        let total = read as f32 + num_assets.saturating_sub(num_read) as f32 * average;
+
+        (done / total).min(1.0)
+    }
+
+    /// Starts recording how long each asset takes to load, including the assets of
+    /// groups. Timings are available from `timings` once the assets are done.
+    pub fn enable_timings(&mut self) {
+        let timings = self.timings.get_or_insert_with(Default::default).clone();
+        for (_, group) in &mut self.groups {
+            group.set_timings(timings.clone());
+        }
+    }
+
+    fn set_timings(&mut self, timings: Arc<Mutex<Vec<AssetTiming>>>) {
+        for (_, group) in &mut self.groups {
+            group.set_timings(timings.clone());
+        }
+        self.timings = Some(timings);
+    }
+
+    /// Returns the timings recorded since `enable_timings` was called,
+    /// slowest asset first.
+    ///
+    /// Only assets loaded from a source are included.
+    pub fn timings(&self) -> Vec<AssetTiming> {
+        let mut timings = self
+            .timings
+            .as_ref()
+            .map(|timings| timings.lock().clone())
+            .unwrap_or_default();
+        timings.sort_by(|a, b| b.total.cmp(&a.total));
+
+        timings
     }
 
     /// Returns `Completion::Complete` if all tracked assets are finished.
+    ///
+    /// Cancelled assets don't count as failed; use `num_cancelled` to check
+    /// whether all assets have actually been loaded.
     pub fn complete(&self) -> Completion {
-        match (
-            self.num_failed.load(Ordering::Relaxed),
-            self.num_loading.load(Ordering::Relaxed),
-        ) {
+        match (self.num_failed(), self.num_loading()) {
             (0, 0) => Completion::Complete,
             (0, _) => Completion::Loading,
             (_, _) => Completion::Failed,
@@ -99,6 +225,13 @@ impl ProgressCounter {
     pub fn is_complete(&self) -> bool {
         self.complete() == Completion::Complete
     }
+
+    fn sum<F>(&self, f: F) -> usize
+    where
+        F: Fn(&ProgressCounter) -> usize + Copy,
+    {
+        f(self) + self.groups.iter().map(|(_, g)| g.sum(f)).sum::<usize>()
+    }
 }
 
 impl<'a> Progress for &'a mut ProgressCounter {
@@ -109,39 +242,98 @@ impl<'a> Progress for &'a mut ProgressCounter {
     }
 
     fn create_tracker(self) -> Self::Tracker {
+        let bytes = self.bytes.clone();
         let errors = self.errors.clone();
+        let num_cancelled = self.num_cancelled.clone();
         let num_failed = self.num_failed.clone();
         let num_loading = self.num_loading.clone();
         num_loading.fetch_add(1, Ordering::Relaxed);
+        let timings = self
+            .timings
+            .clone()
+            .map(|timings| (Instant::now(), timings));
 
         ProgressCounterTracker {
+            bytes,
             errors,
+            num_cancelled,
             num_failed,
             num_loading,
+            read: None,
+            timings,
         }
     }
 }
 
+#[derive(Default, Debug)]
+struct ByteCounts {
+    /// Bytes of all assets read from their source.
+    read: AtomicUsize,
+    /// Number of assets read from their source.
+    num_read: AtomicUsize,
+    /// Bytes of the read assets which finished loading or failed.
+    finished: AtomicUsize,
+    /// Number of read assets which finished loading or failed.
+    num_finished: AtomicUsize,
+}
+
+/// How long it took to load an asset, see `ProgressCounter::enable_timings`.
+#[derive(Clone, Debug)]
+pub struct AssetTiming {
+    /// Name of the asset type.
+    pub asset_type_name: &'static str,
+    /// Name of the asset, usually its path.
+    pub asset_name: String,
+    /// Number of bytes read from the source.
+    pub bytes: usize,
+    /// Time from requesting the load until the asset was read from its source.
+    pub read: Duration,
+    /// Time from requesting the load until the asset was loaded or failed.
+    pub total: Duration,
+    /// `true` if the asset failed to load.
+    pub failed: bool,
+}
+
 /// Progress tracker for `ProgressCounter`.
 #[derive(Default, Debug)]
 pub struct ProgressCounterTracker {
+    bytes: Arc<ByteCounts>,
     errors: Arc<Mutex<Vec<AssetErrorMeta>>>,
+    num_cancelled: Arc<AtomicUsize>,
     num_failed: Arc<AtomicUsize>,
     num_loading: Arc<AtomicUsize>,
+    read: Option<AssetTiming>,
+    timings: Option<(Instant, Arc<Mutex<Vec<AssetTiming>>>)>,
+}
+
+impl ProgressCounterTracker {
+    fn finish(&mut self, failed: bool) {
+        if let Some(ref read) = self.read {
+            self.bytes.finished.fetch_add(read.bytes, Ordering::Relaxed);
+            self.bytes.num_finished.fetch_add(1, Ordering::Relaxed);
+        }
+        if let (Some(mut timing), Some((started, timings))) = (self.read.take(), &self.timings) {
+            timing.total = started.elapsed();
+            timing.failed = failed;
+            timings.lock().push(timing);
+        }
+    }
 }
 
 impl Tracker for ProgressCounterTracker {
-    fn success(self: Box<Self>) {
+    fn success(mut self: Box<Self>) {
+        self.finish(false);
         self.num_loading.fetch_sub(1, Ordering::Relaxed);
     }
 
     fn fail(
-        self: Box<Self>,
+        mut self: Box<Self>,
         handle_id: u32,
         asset_type_name: &'static str,
         asset_name: String,
         error: Error,
     ) {
+        self.finish(true);
         show_error(handle_id, asset_type_name, &asset_name, &error);
         self.errors.lock().push(AssetErrorMeta {
             error,
@@ -155,16 +347,82 @@ impl Tracker for ProgressCounterTracker {
         // the assets that are still loading.
         self.num_loading.fetch_sub(1, Ordering::Relaxed);
     }
+
+    fn cancel(self: Box<Self>, _: u32, _: &'static str, _: String) {
+        self.num_cancelled.fetch_add(1, Ordering::Relaxed);
+        self.num_loading.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn bytes_read(&mut self, asset_type_name: &'static str, asset_name: &str, bytes: usize) {
+        self.bytes.read.fetch_add(bytes, Ordering::Relaxed);
+        self.bytes.num_read.fetch_add(1, Ordering::Relaxed);
+        let read = match self.timings {
+            Some((started, _)) => started.elapsed(),
+            None => Duration::default(),
+        };
+        self.read = Some(AssetTiming {
+            asset_type_name,
+            asset_name: if self.timings.is_some() {
+                asset_name.to_owned()
+            } else {
+                String::new()
+            },
+            bytes,
+            read,
+            total: read,
+            failed: false,
+        });
+    }
 }
 
+/// An asset which failed loading, as returned by `ProgressCounter::errors`.
 #[derive(Debug)]
 pub struct AssetErrorMeta {
+    /// The error, with the asset name at the top and the underlying causes below.
     pub error: Error,
+    /// Id of the handle of the asset.
     pub handle_id: u32,
+    /// `Asset::NAME` of the asset type.
     pub asset_type_name: &'static str,
+    /// Name the asset was loaded with.
     pub asset_name: String,
 }
 
+impl AssetErrorMeta {
+    /// Returns the id of the source the asset was read from, if it failed while being
+    /// imported by the `Loader`. The default source has the id `""`.
+    pub fn source_id(&self) -> Option<&str> {
+        self.import().map(|(source_id, _)| source_id)
+    }
+
+    /// Returns the name of the format the asset was imported with, if it failed while being
+    /// imported by the `Loader`.
+    pub fn format_name(&self) -> Option<&'static str> {
+        self.import().map(|(_, format)| format)
+    }
+
+    /// Returns the position and a snippet of the offending text, if the asset data couldn't
+    /// be parsed.
+    pub fn parse_error(&self) -> Option<&ParseError> {
+        self.error
+            .causes()
+            .filter_map(|e| e.as_error().downcast_ref())
+            .next()
+    }
+
+    fn import(&self) -> Option<(&str, &'static str)> {
+        self.error
+            .causes()
+            .filter_map(|e| match e.as_error().downcast_ref() {
+                Some(crate::error::Error::Import {
+                    source_id, format, ..
+                }) => Some((source_id.as_str(), *format)),
+                _ => None,
+            })
+            .next()
+    }
+}
+
 /// The `Tracker` trait which will be used by the loader to report
 /// back to `Progress`.
 pub trait Tracker: Send + 'static {
@@ -179,6 +437,19 @@ pub trait Tracker: Send + 'static {
         asset_name: String,
         error: Error,
     );
+    /// Called if loading the asset was cancelled before it was imported.
+    ///
+    /// By default, this is reported as a failure.
+    fn cancel(self: Box<Self>, handle_id: u32, asset_type_name: &'static str, asset_name: String) {
+        self.fail(
+            handle_id,
+            asset_type_name,
+            asset_name,
+            crate::error::Error::Cancelled.into(),
+        );
+    }
+    /// Called once the asset has been read from its source, before it is imported.
+    fn bytes_read(&mut self, _asset_type_name: &'static str, _asset_name: &str, _bytes: usize) {}
 }
 
 impl Tracker for () {
@@ -193,6 +464,72 @@ impl Tracker for () {
         show_error(handle_id, asset_type_name, &asset_name, &error);
         error!("Note: to handle the error, use a `Progress` other than `()`");
     }
+    fn cancel(self: Box<Self>, handle_id: u32, asset_type_name: &'static str, asset_name: String) {
+        debug!(
+            "Cancelled loading handle {}, {}, with name {}",
+            handle_id, asset_type_name, asset_name,
+        );
+    }
+}
+
+/// `Progress` calling a closure once the asset finished loading, with the error if it failed
+/// or was cancelled.
+///
+/// The closure is called by the system processing the storage of the asset, once the asset
+/// is available from the storage.
+///
+/// ```rust,ignore
+/// let (sender, receiver) = std::sync::mpsc::channel();
+/// let handle = loader.load(
+///     "level.ron",
+///     RonFormat,
+///     Callback::new(move |result| sender.send(result.is_ok()).unwrap()),
+///     &storage,
+/// );
+/// ```
+#[derive(Debug)]
+pub struct Callback<F>(F);
+
+impl<F> Callback<F>
+where
+    F: FnOnce(Result<(), Error>) + Send + 'static,
+{
+    /// Creates a progress calling `f` once the asset finished loading.
+    pub fn new(f: F) -> Self {
+        Callback(f)
+    }
+}
+
+impl<F> Progress for Callback<F>
+where
+    F: FnOnce(Result<(), Error>) + Send + 'static,
+{
+    type Tracker = Self;
+
+    fn add_assets(&mut self, _: usize) {}
+
+    fn create_tracker(self) -> Self {
+        self
+    }
+}
+
+impl<F> Tracker for Callback<F>
+where
+    F: FnOnce(Result<(), Error>) + Send + 'static,
+{
+    fn success(self: Box<Self>) {
+        (self.0)(Ok(()))
+    }
+
+    fn fail(
+        self: Box<Self>,
+        _handle_id: u32,
+        _asset_type_name: &'static str,
+        _asset_name: String,
+        error: Error,
+    ) {
+        (self.0)(Err(error))
+    }
 }
 
 fn show_error(handle_id: u32, asset_type_name: &'static str, asset_name: &str, error: &Error) {
@@ -209,8 +546,15 @@ fn show_error(handle_id: u32, asset_type_name: &'static str, asset_name: &str, e
 #[cfg(test)]
 mod tests {
     use amethyst_error::Error;
+    use rayon::ThreadPoolBuilder;
 
-    use super::{Completion, Progress, ProgressCounter, Tracker};
+    use std::sync::{mpsc, Arc, Mutex};
+
+    use super::{Callback, Completion, Progress, ProgressCounter, Tracker};
+    use crate::{
+        test_util::{process_until, Level, UnitSource},
+        AssetStorage, Loader, RonFormat,
+    };
 
     #[test]
     fn progress_counter_complete_returns_correct_completion_status_when_loading_or_complete() {
@@ -288,4 +632,137 @@ mod tests {
         tracker_2.success();
         assert_eq!(2, progress.num_finished());
     }
+
+    #[test]
+    fn progress_counter_reports_cancelled_loads_separately() {
+        let mut progress_counter = ProgressCounter::new();
+        let mut progress = &mut progress_counter;
+        progress.add_assets(2);
+        let tracker_0 = Box::new(progress.create_tracker());
+        let tracker_1 = Box::new(progress.create_tracker());
+
+        // 1 cancelled, 1 loading
+        tracker_0.cancel(1, "AssetType", String::from("test.asset"));
+        assert_eq!(Completion::Loading, progress.complete());
+        assert_eq!(1, progress.num_cancelled());
+        assert_eq!(0, progress.num_failed());
+        assert!(progress.errors().is_empty());
+
+        // 1 cancelled, 1 success
+        tracker_1.success();
+        assert_eq!(Completion::Complete, progress.complete());
+        assert_eq!(1, progress.num_finished());
+    }
+
+    #[test]
+    fn progress_counter_includes_groups() {
+        let mut progress = ProgressCounter::new();
+        let mut audio = progress.group("audio");
+        audio.add_assets(1);
+        let tracker_0 = Box::new(audio.create_tracker());
+        let mut geometry = progress.group("geometry");
+        geometry.add_assets(1);
+        let tracker_1 = Box::new(geometry.create_tracker());
+
+        tracker_0.success();
+        assert_eq!(
+            Completion::Complete,
+            progress.get_group("audio").unwrap().complete()
+        );
+        assert_eq!(Completion::Loading, progress.complete());
+        assert_eq!(2, progress.num_assets());
+        assert_eq!(1, progress.num_finished());
+
+        tracker_1.fail(
+            1,
+            "AssetType",
+            String::from("test.asset"),
+            Error::from_string(""),
+        );
+        assert_eq!(Completion::Failed, progress.complete());
+        assert_eq!(1, progress.errors().len());
+    }
+
+    #[test]
+    fn progress_counter_weights_progress_by_bytes() {
+        let mut progress_counter = ProgressCounter::new();
+        progress_counter.enable_timings();
+        let mut progress = &mut progress_counter;
+        progress.add_assets(3);
+        let mut tracker_0 = Box::new(progress.create_tracker());
+        let mut tracker_1 = Box::new(progress.create_tracker());
+        let _tracker_2 = Box::new(progress.create_tracker());
+
+        tracker_0.bytes_read("AssetType", "small.asset", 10);
+        tracker_1.bytes_read("AssetType", "large.asset", 90);
+        tracker_1.success();
+
+        // the unread asset is assumed to have the average size of 50 bytes
+        assert_eq!(100, progress.num_bytes_read());
+        assert!((progress.weighted_progress() - 90.0 / 150.0).abs() < 1e-6);
+
+        tracker_0.success();
+        assert!((progress.weighted_progress() - 100.0 / 150.0).abs() < 1e-6);
+
+        let timings = progress.timings();
+        assert_eq!(2, timings.len());
+        assert_eq!(
+            90,
+            timings
+                .iter()
+                .find(|t| t.asset_name == "large.asset")
+                .unwrap()
+                .bytes
+        );
+    }
+
+    #[test]
+    fn callback_is_called_with_result() {
+        let results = Arc::new(Mutex::new(Vec::new()));
+        for succeed in &[true, false] {
+            let results = results.clone();
+            let progress = Callback::new(move |result: Result<(), Error>| {
+                results.lock().unwrap().push(result.is_ok())
+            });
+            let tracker = Box::new(progress.create_tracker());
+            if *succeed {
+                tracker.success();
+            } else {
+                tracker.fail(
+                    1,
+                    "AssetType",
+                    "missing.asset".to_owned(),
+                    Error::from_string(""),
+                );
+            }
+        }
+
+        assert_eq!(vec![true, false], *results.lock().unwrap());
+    }
+
+    #[test]
+    fn callback_is_called_once_asset_is_processed() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(UnitSource, pool.clone());
+        let mut storage = AssetStorage::<Level>::new();
+        // keeps the asset resident, so the second load waits for the first one
+        storage.set_memory_budget(Some(1024));
+
+        let (sender, receiver) = mpsc::channel();
+        let callback = |sender: mpsc::Sender<bool>| {
+            Callback::new(move |result: Result<(), Error>| sender.send(result.is_ok()).unwrap())
+        };
+        let handle = loader.load("intro.ron", RonFormat, callback(sender.clone()), &storage);
+        let resident = loader.load("intro.ron", RonFormat, callback(sender), &storage);
+        assert_eq!(handle, resident);
+
+        assert!(receiver.try_recv().is_err(), "Called before processing");
+        let mut results = Vec::new();
+        process_until(&mut storage, &pool, |_| {
+            results.extend(receiver.try_iter());
+            !results.is_empty()
+        });
+        assert_eq!(vec![true, true], results);
+        assert!(storage.contains(&handle));
+    }
 }
@@ -0,0 +1,174 @@
+//! Prioritised queue of pending asset loads.
+
+use std::{any::TypeId, sync::Arc};
+
+use parking_lot::Mutex;
+use rayon::ThreadPool;
+
+/// Identifies a pending load by the asset type and handle id.
+pub(crate) type LoadKey = (TypeId, u32);
+
+struct PendingLoad {
+    key: LoadKey,
+    priority: i32,
+    sequence: u64,
+    job: Box<dyn FnOnce(bool) + Send>,
+}
+
+#[derive(Default)]
+struct Pending {
+    loads: Vec<PendingLoad>,
+    next_sequence: u64,
+}
+
+/// Queue of loads which have been requested but not started yet.
+///
+/// Every load spawns a job on the thread pool, which in turn runs the pending load
+/// with the highest priority at that time. Loads with the same priority are run in
+/// the order they were requested.
+#[derive(Clone, Default)]
+pub(crate) struct LoadQueue {
+    pending: Arc<Mutex<Pending>>,
+}
+
+impl LoadQueue {
+    /// Queues a load. `job` is called with `false` once the load is started, or
+    /// with `true` if the load is cancelled before.
+    pub fn push<J>(&self, pool: &ThreadPool, key: LoadKey, priority: i32, job: J)
+    where
+        J: FnOnce(bool) + Send + 'static,
+    {
+        {
+            let mut pending = self.pending.lock();
+            let sequence = pending.next_sequence;
+            pending.next_sequence += 1;
+            pending.loads.push(PendingLoad {
+                key,
+                priority,
+                sequence,
+                job: Box::new(job),
+            });
+        }
+
+        let queue = self.clone();
+        pool.spawn(move || {
+            if let Some(job) = queue.pop() {
+                job(false);
+            }
+        });
+    }
+
+    /// Removes the load with the highest priority from the queue.
+    fn pop(&self) -> Option<Box<dyn FnOnce(bool) + Send>> {
+        let mut pending = self.pending.lock();
+        let index = pending
+            .loads
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| {
+                a.priority
+                    .cmp(&b.priority)
+                    .then_with(|| b.sequence.cmp(&a.sequence))
+            })
+            .map(|(index, _)| index)?;
+
+        Some(pending.loads.swap_remove(index).job)
+    }
+
+    /// Changes the priority of a pending load.
+    /// Returns `false` if the load isn't pending anymore.
+    pub fn set_priority(&self, key: LoadKey, priority: i32) -> bool {
+        match self
+            .pending
+            .lock()
+            .loads
+            .iter_mut()
+            .find(|load| load.key == key)
+        {
+            Some(load) => {
+                load.priority = priority;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancels a pending load.
+    /// Returns `false` if the load isn't pending anymore.
+    pub fn cancel(&self, key: LoadKey) -> bool {
+        let load = {
+            let mut pending = self.pending.lock();
+            pending
+                .loads
+                .iter()
+                .position(|load| load.key == key)
+                .map(|index| pending.loads.swap_remove(index))
+        };
+
+        match load {
+            Some(load) => {
+                (load.job)(true);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the number of loads which haven't been started yet.
+    pub fn num_pending(&self) -> usize {
+        self.pending.lock().loads.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        any::TypeId,
+        sync::{mpsc::channel, Arc},
+    };
+
+    use parking_lot::Mutex;
+    use rayon::ThreadPoolBuilder;
+
+    use super::LoadQueue;
+
+    #[test]
+    fn runs_loads_by_priority_and_skips_cancelled() {
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let queue = LoadQueue::default();
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let cancelled = Arc::new(Mutex::new(Vec::new()));
+
+        // block the only worker thread while queueing
+        let (unblock, blocked) = channel::<()>();
+        pool.spawn(move || blocked.recv().unwrap());
+
+        let (done, finished) = channel();
+        for (id, priority) in [(0, 0), (1, 5), (2, 1), (3, 5)].iter().cloned() {
+            let order = order.clone();
+            let cancelled = cancelled.clone();
+            let done = done.clone();
+            queue.push(&pool, (TypeId::of::<()>(), id), priority, move |cancel| {
+                if cancel {
+                    cancelled.lock().push(id);
+                } else {
+                    order.lock().push(id);
+                }
+                done.send(()).unwrap();
+            });
+        }
+        assert!(queue.set_priority((TypeId::of::<()>(), 0), 10));
+        assert!(queue.cancel((TypeId::of::<()>(), 2)));
+        assert!(!queue.cancel((TypeId::of::<()>(), 2)));
+        assert_eq!(3, queue.num_pending());
+
+        unblock.send(()).unwrap();
+        for _ in 0..4 {
+            finished.recv().unwrap();
+        }
+
+        assert_eq!(vec![0, 1, 3], *order.lock());
+        assert_eq!(vec![2], *cancelled.lock());
+        assert_eq!(0, queue.num_pending());
+    }
+}
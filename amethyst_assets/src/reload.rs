@@ -3,9 +3,10 @@
 use std::{sync::Arc, time::Instant};
 
 use derive_new::new;
+use fnv::FnvHashSet;
 
 use amethyst_core::{
-    ecs::prelude::{DispatcherBuilder, Read, System, SystemData, World, Write},
+    ecs::prelude::{DispatcherBuilder, Read, ReadExpect, System, SystemData, World, Write},
     SystemBundle, SystemDesc, Time,
 };
 use amethyst_error::Error;
@@ -87,6 +88,23 @@ impl HotReloadStrategy {
         }
     }
 
+    /// Only reloads assets whose paths were reported as changed by their source.
+    ///
+    /// This requires sources supporting change notifications (see `Source::poll_changes`),
+    /// e.g. a `Directory` created with `Directory::watched`. Assets loaded from other sources
+    /// are never reloaded with this strategy. Paths reported as changed while a reload is
+    /// pending are reloaded along with it.
+    pub fn on_change() -> Self {
+        use std::u64::MAX;
+
+        HotReloadStrategy {
+            inner: HotReloadStrategyInner::OnChange {
+                changed: FnvHashSet::default(),
+                frame_number: MAX,
+            },
+        }
+    }
+
     /// Never do any hot-reloading.
     pub fn never() -> Self {
         HotReloadStrategy {
@@ -112,9 +130,19 @@ impl HotReloadStrategy {
         match self.inner {
             HotReloadStrategyInner::Every { frame_number, .. } => frame_number == current_frame,
             HotReloadStrategyInner::Trigger { frame_number, .. } => frame_number == current_frame,
+            HotReloadStrategyInner::OnChange { frame_number, .. } => frame_number == current_frame,
             HotReloadStrategyInner::Never => false,
         }
     }
+
+    /// Crate-internal method returning the asset paths that need to be checked for reloads.
+    /// `None` means every asset needs to be checked.
+    pub(crate) fn changed_paths(&self) -> Option<&FnvHashSet<String>> {
+        match self.inner {
+            HotReloadStrategyInner::OnChange { ref changed, .. } => Some(changed),
+            _ => None,
+        }
+    }
 }
 
 impl Default for HotReloadStrategy {
@@ -134,6 +162,10 @@ enum HotReloadStrategyInner {
         triggered: bool,
         frame_number: u64,
     },
+    OnChange {
+        changed: FnvHashSet<String>,
+        frame_number: u64,
+    },
     Never,
 }
 
@@ -160,9 +192,13 @@ impl<'a, 'b> SystemDesc<'a, 'b, HotReloadSystem> for HotReloadSystemDesc {
 pub struct HotReloadSystem;
 
 impl<'a> System<'a> for HotReloadSystem {
-    type SystemData = (Read<'a, Time>, Write<'a, HotReloadStrategy>);
+    type SystemData = (
+        Read<'a, Time>,
+        ReadExpect<'a, Loader>,
+        Write<'a, HotReloadStrategy>,
+    );
 
-    fn run(&mut self, (time, mut strategy): Self::SystemData) {
+    fn run(&mut self, (time, loader, mut strategy): Self::SystemData) {
         #[cfg(feature = "profiler")]
         profile_scope!("hot_reload_system");
 
@@ -186,6 +222,25 @@ impl<'a> System<'a> for HotReloadSystem {
                     *last = Instant::now();
                 }
             }
+            HotReloadStrategyInner::OnChange {
+                ref mut changed,
+                ref mut frame_number,
+            } => {
+                // Storages have already checked the paths of a reload frame that has passed.
+                let pending =
+                    *frame_number != std::u64::MAX && *frame_number >= time.frame_number();
+                if !pending {
+                    changed.clear();
+                }
+                let changes = loader.poll_changes();
+                if !changes.is_empty() {
+                    changed.extend(changes);
+                    // New changes join a pending reload instead of postponing it.
+                    if !pending {
+                        *frame_number = time.frame_number() + 1;
+                    }
+                }
+            }
             HotReloadStrategyInner::Never => {}
         }
     }
@@ -287,3 +342,164 @@ impl<D: 'static> Reload<D> for SingleFile<D> {
         format.import(path, source, Some(objekt::clone(&format)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    };
+
+    use parking_lot::Mutex;
+    use rayon::ThreadPoolBuilder;
+
+    use amethyst_core::ecs::{RunNow, WorldExt};
+    use amethyst_error::format_err;
+
+    use super::*;
+    use crate::{
+        test_util::{process_until, run_until, Level},
+        AssetEvent, AssetStorage, RonFormat,
+    };
+
+    struct ChangingSource {
+        changes: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Source for ChangingSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            Ok(Vec::new())
+        }
+
+        fn poll_changes(&self) -> Option<Vec<String>> {
+            Some(self.changes.lock().drain(..).collect())
+        }
+    }
+
+    #[test]
+    fn on_change_strategy_collects_changed_paths_until_reload_frame_passed() {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let mut world = World::new();
+        world.insert(Time::default());
+        world.insert(Loader::with_default_source(
+            ChangingSource {
+                changes: changes.clone(),
+            },
+            pool,
+        ));
+        let mut system = HotReloadSystemDesc::new(HotReloadStrategy::on_change()).build(&mut world);
+
+        // frame 0: a change is reported and scheduled for the next frame
+        changes.lock().push("a".to_string());
+        system.run_now(&world);
+        {
+            let strategy = world.read_resource::<HotReloadStrategy>();
+            assert!(strategy.needs_reload(1));
+            assert!(strategy.changed_paths().unwrap().contains("a"));
+        }
+
+        // frame 1: the changed paths are kept for storages processing this frame and
+        // another change joins the pending reload instead of postponing it
+        changes.lock().push("b".to_string());
+        world.write_resource::<Time>().increment_frame_number();
+        system.run_now(&world);
+        {
+            let strategy = world.read_resource::<HotReloadStrategy>();
+            assert!(strategy.needs_reload(1));
+            assert!(!strategy.needs_reload(2));
+            let changed = strategy.changed_paths().unwrap();
+            assert!(changed.contains("a"));
+            assert!(changed.contains("b"));
+        }
+
+        // frame 2: the reload frame has passed
+        world.write_resource::<Time>().increment_frame_number();
+        system.run_now(&world);
+        {
+            let strategy = world.read_resource::<HotReloadStrategy>();
+            assert!(!strategy.needs_reload(2));
+            assert!(strategy.changed_paths().unwrap().is_empty());
+        }
+
+        // frame 3: a new change schedules another reload
+        changes.lock().push("c".to_string());
+        world.write_resource::<Time>().increment_frame_number();
+        system.run_now(&world);
+        {
+            let strategy = world.read_resource::<HotReloadStrategy>();
+            assert!(strategy.needs_reload(4));
+            assert_eq!(1, strategy.changed_paths().unwrap().len());
+        }
+    }
+
+    /// Source serving an empty RON value, which is modified whenever `version` is increased.
+    struct VersionedSource {
+        version: Arc<AtomicU64>,
+    }
+
+    impl Source for VersionedSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(self.version.load(Ordering::Relaxed))
+        }
+
+        fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+            Ok(b"()".to_vec())
+        }
+    }
+
+    #[test]
+    fn failed_hot_reloads_emit_failed_events_and_keep_the_asset() {
+        let version = Arc::new(AtomicU64::new(1));
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let mut world = World::new();
+        world.insert(Time::default());
+        world.insert(Loader::with_default_source(
+            VersionedSource {
+                version: version.clone(),
+            },
+            pool.clone(),
+        ));
+        let mut system =
+            HotReloadSystemDesc::new(HotReloadStrategy::when_triggered()).build(&mut world);
+        let mut storage = AssetStorage::<Level>::new();
+        let mut reader = storage.register_event_reader();
+
+        let handle = world
+            .read_resource::<Loader>()
+            .load("intro.ron", RonFormat, (), &storage);
+        process_until(&mut storage, &pool, |s| s.contains(&handle));
+
+        // frame 0: the asset changed and is reloaded in the next frame
+        version.store(2, Ordering::Relaxed);
+        world.write_resource::<HotReloadStrategy>().trigger();
+        system.run_now(&world);
+        let strategy = world.read_resource::<HotReloadStrategy>().clone();
+
+        let mut failed = None;
+        run_until(1, |frame| {
+            storage.process(
+                |_| Err(format_err!("Broken level")),
+                frame,
+                &pool,
+                Some(&strategy),
+            );
+            failed = storage
+                .events()
+                .read(&mut reader)
+                .find_map(|event| match event {
+                    AssetEvent::Failed {
+                        handle_id, name, ..
+                    } if *handle_id == handle.id() => Some(name.clone()),
+                    _ => None,
+                });
+            failed.is_some()
+        });
+        assert_eq!(Some("intro.ron".to_owned()), failed);
+        assert!(storage.contains(&handle));
+    }
+}
@@ -0,0 +1,212 @@
+//! Keeping loaded assets resident within a memory budget.
+
+use fnv::FnvHashMap;
+
+use crate::{Asset, Handle};
+
+/// Keeps strong handles to assets loaded from a path, so they stay loaded after all
+/// other handles are dropped, and evicts the least recently used of them once the
+/// storage exceeds its memory budget.
+pub(crate) struct Residency<A> {
+    budget: usize,
+    resident: FnvHashMap<(String, String), Resident<A>>,
+}
+
+struct Resident<A> {
+    handle: Handle<A>,
+    last_used: u64,
+    touched: bool,
+}
+
+impl<A: Asset> Residency<A> {
+    pub fn new(budget: usize) -> Self {
+        Residency {
+            budget,
+            resident: Default::default(),
+        }
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+    }
+
+    /// Returns the handle of the asset loaded from `name` in `source`, if it's still resident.
+    pub fn get(&mut self, source: &str, name: &str) -> Option<Handle<A>> {
+        self.resident
+            .get_mut(&(source.to_owned(), name.to_owned()))
+            .map(|resident| {
+                resident.touched = true;
+                resident.handle.clone()
+            })
+    }
+
+    pub fn insert(&mut self, source: &str, name: &str, handle: Handle<A>) {
+        self.resident.insert(
+            (source.to_owned(), name.to_owned()),
+            Resident {
+                handle,
+                last_used: 0,
+                touched: true,
+            },
+        );
+    }
+
+    /// Stops keeping the asset with the given handle id resident, e.g. because it failed loading.
+    pub fn remove(&mut self, handle_id: u32) {
+        self.resident
+            .retain(|_, resident| resident.handle.id() != handle_id);
+    }
+
+    /// Evicts least recently used assets until `usage` fits into the budget.
+    ///
+    /// `cost` returns the memory cost of loaded assets and `None` for assets
+    /// that are still loading. Assets which are used elsewhere can't be evicted.
+    /// Returns the number of evicted assets.
+    pub fn evict<F>(&mut self, mut usage: usize, frame_number: u64, cost: F) -> usize
+    where
+        F: Fn(&Handle<A>) -> Option<usize>,
+    {
+        let mut candidates = Vec::new();
+        for (key, resident) in &mut self.resident {
+            // One handle is held by the storage and one by this residency.
+            if resident.touched || resident.handle.strong_count() > 2 {
+                resident.touched = false;
+                resident.last_used = frame_number;
+            } else if let Some(cost) = cost(&resident.handle) {
+                candidates.push((resident.last_used, key.clone(), cost));
+            }
+        }
+        if usage <= self.budget {
+            return 0;
+        }
+
+        candidates.sort_by_key(|&(last_used, _, _)| last_used);
+        let mut evicted = 0;
+        for (_, key, cost) in candidates {
+            if usage <= self.budget {
+                break;
+            }
+            self.resident.remove(&key);
+            usage = usage.saturating_sub(cost);
+            evicted += 1;
+        }
+
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rayon::{ThreadPool, ThreadPoolBuilder};
+
+    use amethyst_core::ecs::VecStorage;
+    use amethyst_error::Error;
+
+    use crate::{
+        test_util::run_until, Asset, AssetStorage, Format, Handle, Loader, ProcessableAsset,
+        ProgressCounter, Source,
+    };
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Blob(Vec<u8>);
+
+    impl Asset for Blob {
+        const NAME: &'static str = "Blob";
+        type Data = Self;
+        type HandleStorage = VecStorage<Handle<Self>>;
+
+        fn memory_cost(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    struct BlobFormat;
+
+    impl Format<Blob> for BlobFormat {
+        fn name(&self) -> &'static str {
+            "Blob"
+        }
+
+        fn import_simple(&self, bytes: Vec<u8>) -> Result<Blob, Error> {
+            Ok(Blob(bytes))
+        }
+    }
+
+    /// Source returning the path bytes as asset contents.
+    struct EchoSource;
+
+    impl Source for EchoSource {
+        fn modified(&self, _: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+            Ok(path.as_bytes().to_vec())
+        }
+    }
+
+    fn process_until_loaded(
+        storage: &mut AssetStorage<Blob>,
+        handle: &Handle<Blob>,
+        frame_number: &mut u64,
+        pool: &ThreadPool,
+    ) {
+        *frame_number = run_until(*frame_number + 1, |frame| {
+            storage.process(ProcessableAsset::process, frame, pool, None);
+            storage.contains(handle)
+        });
+    }
+
+    #[test]
+    fn evicts_least_recently_used_assets_over_budget() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(EchoSource, pool.clone());
+        let mut storage = AssetStorage::<Blob>::new();
+        storage.set_memory_budget(Some(8));
+        let mut frame_number = 0;
+
+        // each asset costs 4 bytes, so only two of them fit into the budget
+        for name in &["aaaa", "bbbb", "cccc"] {
+            let handle = loader.load(*name, BlobFormat, (), &storage);
+            process_until_loaded(&mut storage, &handle, &mut frame_number, &pool);
+        }
+        assert_eq!(8, storage.memory_usage());
+
+        // resident assets are returned without loading them again
+        let resident = loader.load("cccc", BlobFormat, (), &storage);
+        assert_eq!(Some(&Blob(b"cccc".to_vec())), storage.get(&resident));
+
+        // the least recently used asset was evicted and is loaded again
+        let evicted = loader.load("aaaa", BlobFormat, (), &storage);
+        assert!(!storage.contains(&evicted));
+        process_until_loaded(&mut storage, &evicted, &mut frame_number, &pool);
+        assert_eq!(Some(&Blob(b"aaaa".to_vec())), storage.get(&evicted));
+        assert_eq!(8, storage.memory_usage());
+    }
+
+    #[test]
+    fn resident_load_completes_once_asset_is_loaded() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(EchoSource, pool.clone());
+        let mut storage = AssetStorage::<Blob>::new();
+        storage.set_memory_budget(Some(8));
+        let mut frame_number = 0;
+
+        let handle = loader.load("aaaa", BlobFormat, (), &storage);
+        let mut progress = ProgressCounter::new();
+        let resident = loader.load("aaaa", BlobFormat, &mut progress, &storage);
+        assert_eq!(handle, resident);
+        assert!(!progress.is_complete());
+
+        process_until_loaded(&mut storage, &handle, &mut frame_number, &pool);
+        assert!(progress.is_complete());
+        assert_eq!(1, progress.num_finished());
+    }
+}
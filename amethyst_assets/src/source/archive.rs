@@ -0,0 +1,394 @@
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use fnv::FnvHashMap;
+use parking_lot::Mutex;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{error, source::Source};
+
+/// Magic bytes at the start of every pak archive.
+const MAGIC: &[u8; 4] = b"APAK";
+
+/// Version of the pak layout written by `ArchiveBuilder`.
+const VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug)]
+struct Entry {
+    offset: u64,
+    len: u64,
+    modified: u64,
+}
+
+/// Archive source.
+///
+/// Serves assets out of a single pak file, which is a simple container format made of a
+/// header, an index mapping asset paths to byte ranges and the concatenated file contents.
+/// Such archives can be created with `ArchiveBuilder`.
+///
+/// The index is read once when opening the archive; the modification time reported for an
+/// asset is the one recorded in the index when the archive was built.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// let archive = Archive::open("assets.pak")?;
+/// loader.add_source("pak", archive);
+/// ```
+#[derive(Debug)]
+pub struct Archive {
+    loc: PathBuf,
+    file: Mutex<File>,
+    index: FnvHashMap<String, Entry>,
+}
+
+impl Archive {
+    /// Opens the pak archive at the given path and reads its index.
+    pub fn open<P>(loc: P) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        let loc = loc.into();
+        let mut file = File::open(&loc)
+            .with_context(|_| format_err!("Failed to open archive {:?}", loc))
+            .with_context(|_| error::Error::Source)?;
+        let index = file
+            .metadata()
+            .map_err(Error::from)
+            .and_then(|metadata| read_index(&mut file, metadata.len()))
+            .with_context(|_| format_err!("Failed to read index of archive {:?}", loc))
+            .with_context(|_| error::Error::Source)?;
+
+        Ok(Archive {
+            loc,
+            file: Mutex::new(file),
+            index,
+        })
+    }
+
+    /// Returns `true` if the archive contains an asset with the given path.
+    pub fn contains(&self, path: &str) -> bool {
+        self.index.contains_key(path)
+    }
+
+    /// Iterates over the paths of all assets contained in the archive.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    fn entry(&self, path: &str) -> Result<Entry, Error> {
+        self.index
+            .get(path)
+            .cloned()
+            .ok_or_else(|| format_err!("Archive {:?} does not contain {:?}", self.loc, path))
+            .with_context(|_| error::Error::Source)
+    }
+}
+
+impl Source for Archive {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("archive_modified_asset");
+
+        self.entry(path).map(|entry| entry.modified)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("archive_load_asset");
+
+        let entry = self.entry(path)?;
+        let mut v = vec![0; entry.len as usize];
+        let mut file = self.file.lock();
+        file.seek(SeekFrom::Start(entry.offset))
+            .and_then(|_| file.read_exact(&mut v))
+            .with_context(|_| format_err!("Failed to read {:?} from archive {:?}", path, self.loc))
+            .with_context(|_| error::Error::Source)?;
+
+        Ok(v)
+    }
+
+    fn list(&self, dir: &str) -> Result<Vec<String>, Error> {
+        let mut paths = self
+            .index
+            .keys()
+            .filter(|path| in_dir(path, dir))
+            .cloned()
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        Ok(paths)
+    }
+}
+
+/// Returns `true` if `path` is inside of the directory `dir` or one of its subdirectories.
+fn in_dir(path: &str, dir: &str) -> bool {
+    let dir = dir.trim_end_matches('/');
+    dir.is_empty() || (path.starts_with(dir) && path[dir.len()..].starts_with('/'))
+}
+
+/// Builder for pak archives readable by `Archive`.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// let mut builder = ArchiveBuilder::new();
+/// builder.add_directory("assets")?;
+/// builder.write("assets.pak")?;
+/// ```
+#[derive(Debug, Default)]
+pub struct ArchiveBuilder {
+    entries: Vec<(String, Vec<u8>, u64)>,
+}
+
+impl ArchiveBuilder {
+    /// Creates a new, empty archive builder.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds an asset to the archive with the given path, contents and modification time.
+    ///
+    /// The path should always use `/` as separator.
+    pub fn add<P>(&mut self, path: P, bytes: Vec<u8>, modified: u64) -> &mut Self
+    where
+        P: Into<String>,
+    {
+        self.entries.push((path.into(), bytes, modified));
+        self
+    }
+
+    /// Recursively adds every file inside of `dir`, using paths relative to `dir`.
+    pub fn add_directory<P>(&mut self, dir: P) -> Result<&mut Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        use crate::source::Directory;
+
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(format_err!("Failed to read directory {:?}", dir));
+        }
+        let source = Directory::new(dir);
+        for path in source.list("")? {
+            let (bytes, modified) = source.load_with_metadata(&path)?;
+            self.add(path, bytes, modified);
+        }
+
+        Ok(self)
+    }
+
+    /// Writes the archive to the given location.
+    pub fn write<P>(&self, loc: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let loc = loc.as_ref();
+        if let Some((path, _, _)) = self
+            .entries
+            .iter()
+            .find(|(path, _, _)| path.len() > usize::from(u16::max_value()))
+        {
+            return Err(format_err!(
+                "Path {:?} is too long to be stored in archive {:?}",
+                path,
+                loc
+            ));
+        }
+        let mut file =
+            File::create(loc).with_context(|_| format_err!("Failed to create {:?}", loc))?;
+        self.write_to(&mut file)
+            .with_context(|_| format_err!("Failed to write archive {:?}", loc))
+    }
+
+    fn write_to<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        let index_len = self
+            .entries
+            .iter()
+            .map(|(path, _, _)| 2 + path.len() as u64 + 3 * 8)
+            .sum::<u64>();
+        let mut offset = (MAGIC.len() + 4 + 4) as u64 + index_len;
+
+        out.write_all(MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        out.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        for (path, bytes, modified) in &self.entries {
+            let path_len = u16::try_from(path.len()).map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "Path is too long")
+            })?;
+            out.write_all(&path_len.to_le_bytes())?;
+            out.write_all(path.as_bytes())?;
+            out.write_all(&offset.to_le_bytes())?;
+            out.write_all(&(bytes.len() as u64).to_le_bytes())?;
+            out.write_all(&modified.to_le_bytes())?;
+            offset += bytes.len() as u64;
+        }
+        for (_, bytes, _) in &self.entries {
+            out.write_all(bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the index of an archive of `archive_len` bytes, checking that all entries lie
+/// within the archive.
+fn read_index<R: Read>(
+    input: &mut R,
+    archive_len: u64,
+) -> Result<FnvHashMap<String, Entry>, Error> {
+    fn read_u16<R: Read>(input: &mut R) -> std::io::Result<u16> {
+        let mut buf = [0; 2];
+        input.read_exact(&mut buf).map(|_| u16::from_le_bytes(buf))
+    }
+    fn read_u32<R: Read>(input: &mut R) -> std::io::Result<u32> {
+        let mut buf = [0; 4];
+        input.read_exact(&mut buf).map(|_| u32::from_le_bytes(buf))
+    }
+    fn read_u64<R: Read>(input: &mut R) -> std::io::Result<u64> {
+        let mut buf = [0; 8];
+        input.read_exact(&mut buf).map(|_| u64::from_le_bytes(buf))
+    }
+
+    let mut magic = [0; 4];
+    input.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(format_err!("Not a pak archive"));
+    }
+    let version = read_u32(input)?;
+    if version != VERSION {
+        return Err(format_err!("Unsupported pak archive version {}", version));
+    }
+
+    let count = read_u32(input)?;
+    let mut index = FnvHashMap::default();
+    let mut index_end = (MAGIC.len() + 4 + 4) as u64;
+    for _ in 0..count {
+        let path_len = read_u16(input)?;
+        let mut path = vec![0; usize::from(path_len)];
+        input.read_exact(&mut path)?;
+        let path = String::from_utf8(path)?;
+        let entry = Entry {
+            offset: read_u64(input)?,
+            len: read_u64(input)?,
+            modified: read_u64(input)?,
+        };
+        index_end += 2 + u64::from(path_len) + 3 * 8;
+        index.insert(path, entry);
+    }
+    for (path, entry) in &index {
+        let in_bounds = entry.offset >= index_end
+            && entry
+                .offset
+                .checked_add(entry.len)
+                .map_or(false, |end| end <= archive_len);
+        if !in_bounds {
+            return Err(format_err!(
+                "Entry {:?} lies outside of the archive contents",
+                path
+            ));
+        }
+    }
+
+    Ok(index)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use crate::source::Source;
+
+    use super::{Archive, ArchiveBuilder};
+
+    fn temp_archive(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("amethyst_assets_archive_test");
+        std::fs::create_dir_all(&dir).expect("Failed to create temporary directory");
+        dir.join(name)
+    }
+
+    #[test]
+    fn loads_assets_from_archive() {
+        let loc = temp_archive("loads_assets_from_archive.pak");
+        ArchiveBuilder::new()
+            .add("a/first", b"first".to_vec(), 1)
+            .add("second", b"second".to_vec(), 2)
+            .write(&loc)
+            .expect("Failed to write archive");
+
+        let archive = Archive::open(&loc).expect("Failed to open archive");
+
+        assert_eq!(b"first".to_vec(), archive.load("a/first").unwrap());
+        assert_eq!(
+            (b"second".to_vec(), 2),
+            archive.load_with_metadata("second").unwrap()
+        );
+        assert_eq!(1, archive.modified("a/first").unwrap());
+        assert!(archive.load("missing").is_err());
+        assert_eq!(vec!["a/first", "second"], archive.list("").unwrap());
+        assert_eq!(vec!["a/first"], archive.list("a").unwrap());
+        assert!(archive.list("b").unwrap().is_empty());
+    }
+
+    #[test]
+    fn packs_assets_directory() {
+        let test_assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/assets");
+        let loc = temp_archive("packs_assets_directory.pak");
+        ArchiveBuilder::new()
+            .add_directory(test_assets_dir)
+            .expect("Failed to pack tests/assets")
+            .write(&loc)
+            .expect("Failed to write archive");
+
+        let archive = Archive::open(&loc).expect("Failed to open archive");
+
+        assert_eq!(
+            b"data".to_vec(),
+            archive
+                .load("subdir/asset")
+                .expect("Failed to load subdir/asset from archive")
+        );
+    }
+
+    #[test]
+    fn rejects_files_without_pak_header() {
+        let loc = temp_archive("rejects_files_without_pak_header.pak");
+        std::fs::write(&loc, b"not an archive").expect("Failed to write file");
+
+        assert!(Archive::open(&loc).is_err());
+    }
+
+    #[test]
+    fn rejects_entries_outside_of_archive() {
+        let loc = temp_archive("rejects_entries_outside_of_archive.pak");
+        ArchiveBuilder::new()
+            .add("asset", b"data".to_vec(), 0)
+            .write(&loc)
+            .expect("Failed to write archive");
+        let mut bytes = std::fs::read(&loc).expect("Failed to read archive");
+        // Cut off the end of the asset contents.
+        bytes.truncate(bytes.len() - 2);
+        std::fs::write(&loc, bytes).expect("Failed to write file");
+
+        assert!(Archive::open(&loc).is_err());
+    }
+
+    #[test]
+    fn rejects_too_long_paths() {
+        let loc = temp_archive("rejects_too_long_paths.pak");
+        let path = "a".repeat(usize::from(u16::max_value()) + 1);
+
+        assert!(ArchiveBuilder::new()
+            .add(path, Vec::new(), 0)
+            .write(&loc)
+            .is_err());
+    }
+}
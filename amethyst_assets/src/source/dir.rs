@@ -17,9 +17,15 @@ use crate::{error, source::Source};
 /// inside the `Loader`, which is automatically used when you call
 /// `load`. In case you want another, second, directory for assets,
 /// you can instantiate one yourself, too. Please use `Loader::load_from` then.
+///
+/// With the `fs_notify` feature enabled, a directory created with `Directory::watched`
+/// receives change notifications from the file system (inotify on Linux), which are reported
+/// through `Source::poll_changes`.
 #[derive(Debug)]
 pub struct Directory {
     loc: PathBuf,
+    #[cfg(feature = "fs_notify")]
+    watcher: Option<watch::DirectoryWatcher>,
 }
 
 impl Directory {
@@ -28,7 +34,27 @@ impl Directory {
     where
         P: Into<PathBuf>,
     {
-        Directory { loc: loc.into() }
+        Directory {
+            loc: loc.into(),
+            #[cfg(feature = "fs_notify")]
+            watcher: None,
+        }
+    }
+
+    /// Creates a new directory storage which watches `loc` for changes.
+    #[cfg(feature = "fs_notify")]
+    pub fn watched<P>(loc: P) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        let loc = loc.into();
+        let watcher = watch::DirectoryWatcher::new(&loc)
+            .with_context(|_| format_err!("Failed to watch directory {:?}", loc))?;
+
+        Ok(Directory {
+            loc,
+            watcher: Some(watcher),
+        })
     }
 
     fn path(&self, s_path: &str) -> PathBuf {
@@ -75,6 +101,124 @@ impl Source for Directory {
 
         Ok(v)
     }
+
+    fn list(&self, dir: &str) -> Result<Vec<String>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("dir_list_assets");
+
+        let root = self.path(dir);
+        if !root.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut paths = Vec::new();
+        let mut pending = vec![PathBuf::new()];
+        while let Some(rel) = pending.pop() {
+            let abs = root.join(&rel);
+            let read_dir = std::fs::read_dir(&abs)
+                .with_context(|_| format_err!("Failed to read directory {:?}", abs))?;
+            for dir_entry in read_dir {
+                let dir_entry = dir_entry
+                    .with_context(|_| format_err!("Failed to read directory {:?}", abs))?;
+                let file_type = dir_entry
+                    .file_type()
+                    .with_context(|_| format_err!("Failed to read directory {:?}", abs))?;
+                let rel = rel.join(dir_entry.file_name());
+                if file_type.is_dir() {
+                    pending.push(rel);
+                    continue;
+                }
+                // symlinked directories aren't followed, as they may lead back into the source
+                if file_type.is_symlink() && !dir_entry.path().is_file() {
+                    continue;
+                }
+                let path = Path::new(dir)
+                    .join(rel)
+                    .iter()
+                    .map(|c| c.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        Ok(paths)
+    }
+
+    #[cfg(feature = "fs_notify")]
+    fn poll_changes(&self) -> Option<Vec<String>> {
+        self.watcher.as_ref().map(watch::DirectoryWatcher::changes)
+    }
+}
+
+#[cfg(feature = "fs_notify")]
+mod watch {
+    use std::{
+        path::{Path, PathBuf},
+        sync::mpsc::{channel, Receiver},
+        time::Duration,
+    };
+
+    use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+    use parking_lot::Mutex;
+
+    use amethyst_error::Error;
+
+    /// Delay used to merge multiple file system events for the same file.
+    const DEBOUNCE: Duration = Duration::from_millis(50);
+
+    pub struct DirectoryWatcher {
+        root: PathBuf,
+        events: Mutex<Receiver<DebouncedEvent>>,
+        _watcher: Mutex<RecommendedWatcher>,
+    }
+
+    impl DirectoryWatcher {
+        pub fn new(loc: &Path) -> Result<Self, Error> {
+            let root = loc.canonicalize()?;
+            let (tx, rx) = channel();
+            let mut watcher: RecommendedWatcher = Watcher::new(tx, DEBOUNCE)?;
+            watcher.watch(&root, RecursiveMode::Recursive)?;
+
+            Ok(DirectoryWatcher {
+                root,
+                events: Mutex::new(rx),
+                _watcher: Mutex::new(watcher),
+            })
+        }
+
+        /// Drains all pending events and returns the changed paths relative to the root,
+        /// using `/` as separator.
+        pub fn changes(&self) -> Vec<String> {
+            self.events
+                .lock()
+                .try_iter()
+                .filter_map(|event| match event {
+                    DebouncedEvent::Create(path)
+                    | DebouncedEvent::Write(path)
+                    | DebouncedEvent::Chmod(path)
+                    | DebouncedEvent::Rename(_, path) => Some(path),
+                    _ => None,
+                })
+                .filter_map(|path| {
+                    path.strip_prefix(&self.root).ok().map(|rel| {
+                        rel.iter()
+                            .map(|c| c.to_string_lossy())
+                            .collect::<Vec<_>>()
+                            .join("/")
+                    })
+                })
+                .collect()
+        }
+    }
+
+    impl std::fmt::Debug for DirectoryWatcher {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("DirectoryWatcher")
+                .field("root", &self.root)
+                .finish()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -98,6 +242,30 @@ mod test {
         );
     }
 
+    #[test]
+    fn lists_assets_in_subdirectories() {
+        let test_assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/assets");
+        let directory = Directory::new(test_assets_dir);
+
+        assert_eq!(vec!["subdir/asset"], directory.list("").unwrap());
+        assert_eq!(vec!["subdir/asset"], directory.list("subdir").unwrap());
+        assert!(directory.list("missing").unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn does_not_follow_symlinked_directories() {
+        let root = std::env::temp_dir().join(format!("amethyst_dir_list_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("asset"), b"data").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("loop")).unwrap();
+        std::os::unix::fs::symlink(root.join("asset"), root.join("link")).unwrap();
+
+        let listed = Directory::new(root.clone()).list("");
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(vec!["asset", "link"], listed.unwrap());
+    }
+
     #[cfg(windows)]
     #[test]
     fn tolerates_backslashed_location_with_forward_slashed_asset_paths() {
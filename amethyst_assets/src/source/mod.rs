@@ -1,11 +1,19 @@
-use amethyst_error::Error;
+use amethyst_error::{format_err, Error};
 
-pub use self::dir::Directory;
+pub(crate) use self::pattern::Pattern;
+pub use self::{
+    archive::{Archive, ArchiveBuilder},
+    dir::Directory,
+    overlay::Overlay,
+};
 
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
+mod archive;
 mod dir;
+mod overlay;
+mod pattern;
 
 /// A trait for asset sources, which provides
 /// methods for loading bytes.
@@ -32,4 +40,23 @@ pub trait Source: Send + Sync + 'static {
 
         Ok((b, m))
     }
+
+    /// Returns the paths which changed since the last call to this method.
+    ///
+    /// Sources which can be notified about changes (e.g. by the file system) should
+    /// implement this, which allows `HotReloadStrategy::on_change` to only reload affected
+    /// assets instead of polling `modified` for every one of them.
+    /// Returns `None` if this source doesn't support change notifications, which is the default.
+    fn poll_changes(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Returns the paths of all assets inside of the directory `dir` and its subdirectories,
+    /// using `/` as separator. `dir` is given relative to the source, `""` lists all assets.
+    ///
+    /// This allows loading all assets matching a pattern with `Loader::load_matching`.
+    /// Sources which can't enumerate their assets return an error, which is the default.
+    fn list(&self, dir: &str) -> Result<Vec<String>, Error> {
+        Err(format_err!("Source can't list the assets in {:?}", dir))
+    }
 }
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_error::{format_err, Error, ResultExt};
+
+use crate::{error, source::Source};
+
+/// Layered source.
+///
+/// Stacks several sources on top of each other and resolves every path from the
+/// highest-priority layer that contains it. Layers added later take priority over
+/// layers added earlier, so a typical setup adds the base game assets first, followed
+/// by DLC archives and finally the user's mod folder.
+///
+/// Whether a layer contains a path is determined by asking it for the modification time,
+/// which is also what `modified` reports for the winning layer. Hot reloading therefore
+/// picks up an override once its modification time is newer than the one of the asset
+/// it replaces.
+///
+/// ## Examples
+///
+/// ```rust,ignore
+/// let source = Overlay::new()
+///     .with_layer(Directory::new("assets"))
+///     .with_layer(Archive::open("dlc.pak")?)
+///     .with_layer(Directory::new("mods"));
+/// loader.set_default_source(source);
+/// ```
+#[derive(Default)]
+pub struct Overlay {
+    layers: Vec<Arc<dyn Source>>,
+}
+
+impl Overlay {
+    /// Creates a new overlay without any layers.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a layer with a higher priority than all existing layers.
+    pub fn with_layer<S>(mut self, source: S) -> Self
+    where
+        S: Source,
+    {
+        self.push_layer(source);
+        self
+    }
+
+    /// Adds a layer with a higher priority than all existing layers.
+    pub fn push_layer<S>(&mut self, source: S)
+    where
+        S: Source,
+    {
+        self.layers.push(Arc::new(source) as Arc<dyn Source>);
+    }
+
+    /// Returns the number of layers.
+    pub fn num_layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns the index of the layer that `path` is resolved from, if any.
+    ///
+    /// The base layer has index `0`.
+    pub fn resolve(&self, path: &str) -> Option<usize> {
+        self.winning_layer(path).ok().map(|(index, _)| index)
+    }
+
+    fn winning_layer(&self, path: &str) -> Result<(usize, u64), Error> {
+        self.layers
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(index, layer)| layer.modified(path).ok().map(|m| (index, m)))
+            .ok_or_else(|| format_err!("No layer of the overlay contains {:?}", path))
+            .with_context(|_| error::Error::Source)
+    }
+}
+
+impl Source for Overlay {
+    fn modified(&self, path: &str) -> Result<u64, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("overlay_modified_asset");
+
+        self.winning_layer(path).map(|(_, m)| m)
+    }
+
+    fn load(&self, path: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("overlay_load_asset");
+
+        let (index, _) = self.winning_layer(path)?;
+        self.layers[index].load(path)
+    }
+
+    fn load_with_metadata(&self, path: &str) -> Result<(Vec<u8>, u64), Error> {
+        #[cfg(feature = "profiler")]
+        profile_scope!("overlay_load_asset_with_metadata");
+
+        let (index, m) = self.winning_layer(path)?;
+        let b = self.layers[index].load(path)?;
+
+        Ok((b, m))
+    }
+
+    fn list(&self, dir: &str) -> Result<Vec<String>, Error> {
+        let mut paths = Vec::new();
+        for layer in &self.layers {
+            paths.extend(layer.list(dir)?);
+        }
+        paths.sort();
+        paths.dedup();
+
+        Ok(paths)
+    }
+
+    fn poll_changes(&self) -> Option<Vec<String>> {
+        self.layers
+            .iter()
+            .filter_map(|layer| layer.poll_changes())
+            .fold(None, |changes: Option<Vec<String>>, layer_changes| {
+                let mut changes = changes.unwrap_or_default();
+                changes.extend(layer_changes);
+                Some(changes)
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::source::{Archive, ArchiveBuilder, Directory, Source};
+
+    use super::Overlay;
+
+    fn archive(name: &str, entries: &[(&str, &[u8], u64)]) -> Archive {
+        let dir = std::env::temp_dir().join("amethyst_assets_overlay_test");
+        std::fs::create_dir_all(&dir).expect("Failed to create temporary directory");
+        let loc = dir.join(name);
+
+        let mut builder = ArchiveBuilder::new();
+        for (path, bytes, modified) in entries {
+            builder.add(*path, bytes.to_vec(), *modified);
+        }
+        builder.write(&loc).expect("Failed to write archive");
+
+        Archive::open(&loc).expect("Failed to open archive")
+    }
+
+    #[test]
+    fn resolves_paths_from_highest_priority_layer() {
+        let overlay = Overlay::new()
+            .with_layer(archive(
+                "base.pak",
+                &[("shared", b"base", 1), ("base_only", b"base", 1)],
+            ))
+            .with_layer(archive("mod.pak", &[("shared", b"mod", 5)]));
+
+        assert_eq!(b"mod".to_vec(), overlay.load("shared").unwrap());
+        assert_eq!(5, overlay.modified("shared").unwrap());
+        assert_eq!(b"base".to_vec(), overlay.load("base_only").unwrap());
+        assert_eq!(
+            (b"base".to_vec(), 1),
+            overlay.load_with_metadata("base_only").unwrap()
+        );
+        assert_eq!(Some(1), overlay.resolve("shared"));
+        assert_eq!(Some(0), overlay.resolve("base_only"));
+        assert_eq!(vec!["base_only", "shared"], overlay.list("").unwrap());
+    }
+
+    #[test]
+    fn fails_when_no_layer_contains_path() {
+        let test_assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/assets");
+        let overlay = Overlay::new().with_layer(Directory::new(test_assets_dir));
+
+        assert_eq!(b"data".to_vec(), overlay.load("subdir/asset").unwrap());
+        assert!(overlay.load("subdir/missing").is_err());
+        assert!(overlay.modified("subdir/missing").is_err());
+        assert_eq!(None, overlay.resolve("subdir/missing"));
+    }
+}
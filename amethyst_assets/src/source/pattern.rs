@@ -0,0 +1,149 @@
+/// Glob pattern for asset paths, used by `Loader::load_matching`.
+///
+/// * `?` matches any character except `/`
+/// * `*` matches any number of characters except `/`
+/// * `**` matches any number of characters, including `/`
+///
+/// All other characters match themselves.
+#[derive(Clone, Debug)]
+pub(crate) struct Pattern {
+    pattern: Vec<char>,
+    tokens: Vec<Token>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Token {
+    Char(char),
+    /// `?`
+    AnyChar,
+    /// `*`
+    AnyInDir,
+    /// `**`
+    Any,
+    /// `**/`, which also matches no directory at all
+    AnyDirs,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Self {
+        let pattern = pattern.chars().collect::<Vec<_>>();
+        Pattern {
+            tokens: tokenize(&pattern),
+            pattern,
+        }
+    }
+
+    /// Returns the directory containing all matching paths, i.e. the part of the pattern
+    /// up to the last `/` before the first wildcard.
+    pub fn dir(&self) -> String {
+        let literal = self
+            .pattern
+            .iter()
+            .position(|c| *c == '*' || *c == '?')
+            .unwrap_or_else(|| self.pattern.len());
+        let dir = self.pattern[..literal]
+            .iter()
+            .rposition(|c| *c == '/')
+            .unwrap_or(0);
+
+        self.pattern[..dir].iter().collect()
+    }
+
+    /// Returns `true` if `path` matches the whole pattern.
+    ///
+    /// Takes time proportional to the length of the pattern times the length of the path.
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.chars().collect::<Vec<_>>();
+        // `matched[i]` is `true` if the tokens after the current one match `path[i..]`
+        let mut matched = vec![false; path.len() + 1];
+        matched[path.len()] = true;
+        for token in self.tokens.iter().rev() {
+            let mut current = vec![false; path.len() + 1];
+            let mut dir_matched = false;
+            for i in (0..=path.len()).rev() {
+                let c = path.get(i).cloned();
+                current[i] = match *token {
+                    Token::Char(t) => c == Some(t) && matched[i + 1],
+                    Token::AnyChar => c.map_or(false, |c| c != '/') && matched[i + 1],
+                    Token::AnyInDir => {
+                        matched[i] || (c.map_or(false, |c| c != '/') && current[i + 1])
+                    }
+                    Token::Any => matched[i] || (c.is_some() && current[i + 1]),
+                    Token::AnyDirs => {
+                        dir_matched = dir_matched || (c == Some('/') && matched[i + 1]);
+                        matched[i] || dir_matched
+                    }
+                };
+            }
+            matched = current;
+        }
+        matched[0]
+    }
+}
+
+fn tokenize(pattern: &[char]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < pattern.len() {
+        let token = match pattern[i] {
+            '*' if pattern.get(i + 1) == Some(&'*') => {
+                i += 1;
+                if pattern.get(i + 1) == Some(&'/') {
+                    i += 1;
+                    Token::AnyDirs
+                } else {
+                    Token::Any
+                }
+            }
+            '*' => Token::AnyInDir,
+            '?' => Token::AnyChar,
+            c => Token::Char(c),
+        };
+        tokens.push(token);
+        i += 1;
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod test {
+    use super::Pattern;
+
+    #[test]
+    fn matches_wildcards() {
+        let pattern = Pattern::new("sfx/*.ogg");
+        assert!(pattern.matches("sfx/jump.ogg"));
+        assert!(!pattern.matches("sfx/ui/click.ogg"));
+        assert!(!pattern.matches("sfx/jump.wav"));
+
+        let pattern = Pattern::new("levels/**/*.ron");
+        assert!(pattern.matches("levels/intro.ron"));
+        assert!(pattern.matches("levels/world1/boss.ron"));
+        assert!(!pattern.matches("levels/world1/boss.ron.bak"));
+
+        let pattern = Pattern::new("level?.ron");
+        assert!(pattern.matches("level1.ron"));
+        assert!(!pattern.matches("level10.ron"));
+
+        let pattern = Pattern::new("**/sfx/**");
+        assert!(pattern.matches("sfx/jump.ogg"));
+        assert!(pattern.matches("world1/sfx/ui/click.ogg"));
+        assert!(!pattern.matches("world1/music/theme.ogg"));
+    }
+
+    #[test]
+    fn many_wildcards_match_quickly() {
+        let path = "a".repeat(1000);
+        assert!(!Pattern::new("*a*a*a*a*a*a*a*a*a*a*b").matches(&path));
+        assert!(!Pattern::new("**a**a**a**a**a**a**a**a**b").matches(&path));
+        assert!(Pattern::new("*a*a*a*a*a*a*a*a*a*a").matches(&path));
+    }
+
+    #[test]
+    fn dir_ends_before_first_wildcard() {
+        assert_eq!("sfx", Pattern::new("sfx/*.ogg").dir());
+        assert_eq!("levels/world1", Pattern::new("levels/world1/**").dir());
+        assert_eq!("", Pattern::new("*.ron").dir());
+        assert_eq!("a", Pattern::new("a/b.ron").dir());
+    }
+}
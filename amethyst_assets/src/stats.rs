@@ -0,0 +1,225 @@
+//! Statistics about loaded assets.
+
+use std::{
+    any::TypeId,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use fnv::FnvHashMap;
+use parking_lot::{Mutex, MutexGuard};
+
+use crate::{Asset, AssetStorage};
+
+/// Statistics of an `AssetStorage`, as returned by `AssetStorage::stats`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssetStorageStats {
+    /// `Asset::NAME` of the asset type.
+    pub asset_type_name: &'static str,
+    /// Number of loaded assets.
+    pub num_assets: usize,
+    /// Number of assets waiting to be processed.
+    pub num_pending: usize,
+    /// Number of loaded assets without handles outside of the storage, which are dropped
+    /// the next time the storage is processed.
+    pub num_unused: usize,
+    /// Number of bytes read from sources for assets of this type.
+    pub bytes_loaded: usize,
+    /// Sum of `Asset::memory_cost` of the loaded assets.
+    pub memory_usage: usize,
+}
+
+/// Resource aggregating the statistics of all asset storages, e.g. for a debug overlay
+/// listing what's resident.
+///
+/// `Processor` and the other systems processing asset storages update the statistics of
+/// their storage every frame with `update`. As collecting them walks all assets of the
+/// storage, they're only collected again after they were read, so they lag up to a frame
+/// behind.
+#[derive(Debug, Default)]
+pub struct AssetStats {
+    /// The statistics by asset type, with the number of reads when they were collected.
+    storages: Mutex<FnvHashMap<TypeId, (AssetStorageStats, usize)>>,
+    reads: AtomicUsize,
+}
+
+impl AssetStats {
+    /// Collects the statistics of `storage`, unless they weren't read since they were last
+    /// collected.
+    pub fn update<A: Asset>(&self, storage: &AssetStorage<A>) {
+        let id = TypeId::of::<A>();
+        let reads = self.reads.load(Ordering::Relaxed);
+        let stale = self
+            .storages
+            .lock()
+            .get(&id)
+            .map_or(true, |(_, collected)| *collected != reads);
+        if stale {
+            let stats = storage.stats();
+            self.storages.lock().insert(id, (stats, reads));
+        }
+    }
+
+    /// Returns the statistics of the storage of the asset type `A`.
+    pub fn get<A: Asset>(&self) -> Option<AssetStorageStats> {
+        self.read()
+            .get(&TypeId::of::<A>())
+            .map(|(stats, _)| stats.clone())
+    }
+
+    /// Returns the statistics of all storages, sorted by asset type name.
+    pub fn all(&self) -> Vec<AssetStorageStats> {
+        let mut all = self
+            .read()
+            .values()
+            .map(|(stats, _)| stats.clone())
+            .collect::<Vec<_>>();
+        all.sort_by_key(|stats| stats.asset_type_name);
+        all
+    }
+
+    /// Returns the number of loaded assets of all types.
+    pub fn num_assets(&self) -> usize {
+        self.read().values().map(|(s, _)| s.num_assets).sum()
+    }
+
+    /// Returns the number of bytes read from sources for assets of all types.
+    pub fn bytes_loaded(&self) -> usize {
+        self.read().values().map(|(s, _)| s.bytes_loaded).sum()
+    }
+
+    fn read(&self) -> MutexGuard<'_, FnvHashMap<TypeId, (AssetStorageStats, usize)>> {
+        self.reads.fetch_add(1, Ordering::Relaxed);
+        self.storages.lock()
+    }
+}
+
+/// Shared between an `AssetStorage` and the loads of its assets, which report the bytes
+/// they read once the data was imported.
+#[derive(Debug, Default)]
+pub(crate) struct LoadStats {
+    bytes: AtomicUsize,
+    imported: Mutex<FnvHashMap<u32, (usize, Instant)>>,
+}
+
+impl LoadStats {
+    /// Records that the data of the asset with handle id `id` was imported from `bytes` bytes.
+    pub fn imported(&self, id: u32, bytes: usize) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        self.imported.lock().insert(id, (bytes, Instant::now()));
+    }
+
+    /// Removes the bytes read for and the time of importing the asset with handle id `id`.
+    pub fn take_imported(&self, id: u32) -> Option<(usize, Instant)> {
+        self.imported.lock().remove(&id)
+    }
+
+    /// Returns the number of bytes read for all imported assets.
+    pub fn bytes(&self) -> usize {
+        self.bytes.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rayon::ThreadPoolBuilder;
+
+    use amethyst_core::ecs::VecStorage;
+
+    use super::*;
+    use crate::{
+        test_util::{process_until, Level, UnitSource},
+        Handle, Loader, RonFormat,
+    };
+
+    struct Mesh;
+
+    impl Asset for Mesh {
+        const NAME: &'static str = "Mesh";
+        type Data = ();
+        type HandleStorage = VecStorage<Handle<Self>>;
+    }
+
+    /// Asset type with the same name as `Level`.
+    struct Save;
+
+    impl Asset for Save {
+        const NAME: &'static str = "Level";
+        type Data = ();
+        type HandleStorage = VecStorage<Handle<Self>>;
+    }
+
+    #[test]
+    fn storage_stats_count_unused_assets() {
+        let mut storage = AssetStorage::<Level>::new();
+        let _intro = storage.insert(Level);
+        let outro = storage.insert(Level);
+        drop(outro);
+
+        let stats = storage.stats();
+        assert_eq!("Level", stats.asset_type_name);
+        assert_eq!(2, stats.num_assets);
+        assert_eq!(1, stats.num_unused);
+        assert_eq!(0, stats.num_pending);
+    }
+
+    #[test]
+    fn stats_are_aggregated_over_asset_types() {
+        let stats = AssetStats::default();
+        let mut levels = AssetStorage::<Level>::new();
+        let mut meshes = AssetStorage::<Mesh>::new();
+        let saves = AssetStorage::<Save>::new();
+        let _intro = levels.insert(Level);
+        let _cube = meshes.insert(Mesh);
+        let _sphere = meshes.insert(Mesh);
+        stats.update(&levels);
+        stats.update(&meshes);
+        stats.update(&saves);
+
+        // asset types with the same name are told apart
+        let names = stats
+            .all()
+            .iter()
+            .map(|s| s.asset_type_name)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["Level", "Level", "Mesh"], names);
+        assert_eq!(3, stats.num_assets());
+        assert_eq!(Some(0), stats.get::<Save>().map(|s| s.num_assets));
+
+        let _outro = levels.insert(Level);
+        stats.update(&levels);
+        let _credits = levels.insert(Level);
+        // the stats weren't read since they were collected, so they aren't collected again
+        stats.update(&levels);
+        assert_eq!(Some(2), stats.get::<Level>().map(|s| s.num_assets));
+        stats.update(&levels);
+        assert_eq!(Some(3), stats.get::<Level>().map(|s| s.num_assets));
+    }
+
+    #[test]
+    fn loads_are_timed_and_their_bytes_counted() {
+        let pool = Arc::new(ThreadPoolBuilder::default().build().unwrap());
+        let loader = Loader::with_default_source(UnitSource, pool.clone());
+        let mut storage = AssetStorage::<Level>::new();
+        let intro = loader.load("intro.ron", RonFormat, (), &storage);
+        let outro = loader.load("outro.ron", RonFormat, (), &storage);
+        process_until(&mut storage, &pool, |s| {
+            s.contains(&intro) && s.contains(&outro)
+        });
+
+        let timing = storage.timing(&intro).unwrap();
+        assert_eq!("Level", timing.asset_type_name);
+        assert_eq!("intro.ron", timing.asset_name);
+        assert_eq!(2, timing.bytes);
+        assert!(timing.read <= timing.total);
+        assert!(!timing.failed);
+        let timings = storage.timings();
+        assert_eq!(2, timings.len());
+        assert!(timings[0].total >= timings[1].total);
+
+        assert_eq!(4, loader.bytes_loaded());
+        assert_eq!(4, storage.stats().bytes_loaded);
+    }
+}
@@ -1,34 +1,44 @@
 use std::{
+    any::Any,
     marker::PhantomData,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Weak,
     },
+    time::Instant,
 };
 
 use crossbeam_queue::SegQueue;
 use derivative::Derivative;
+use fnv::FnvHashMap;
 use log::{debug, error, trace, warn};
+use parking_lot::Mutex;
 use rayon::ThreadPool;
 
 use amethyst_core::{
     ecs::{
-        hibitset::BitSet,
+        hibitset::{BitSet, BitSetLike},
         prelude::{Component, Read, ReadExpect, System, SystemData, VecStorage, World, Write},
         storage::UnprotectedStorage,
     },
+    shrev::{EventChannel, ReaderId},
     SystemDesc, Time,
 };
-use amethyst_error::{Error, ResultExt};
+use amethyst_error::{format_err, Error, ResultExt};
 
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
 use crate::{
     asset::{Asset, FormatValue, ProcessableAsset},
+    dependency::{self, Dependency},
+    dyn_format::cast_format,
     error,
-    progress::Tracker,
+    event::AssetEvent,
+    progress::{AssetTiming, Tracker},
     reload::{HotReloadStrategy, Reload},
+    residency::Residency,
+    stats::{AssetStats, AssetStorageStats, LoadStats},
 };
 
 /// An `Allocator`, holding a counter for producing unique IDs.
@@ -49,11 +59,90 @@ impl Allocator {
 pub struct AssetStorage<A: Asset> {
     assets: VecStorage<(A, u32)>,
     bitset: BitSet,
+    dependencies: FnvHashMap<u32, Vec<Dependency>>,
+    events: EventChannel<AssetEvent<A>>,
     handles: Vec<Handle<A>>,
     handle_alloc: Allocator,
+    pub(crate) load_stats: Arc<LoadStats>,
+    origins: Mutex<Origins<A>>,
     pub(crate) processed: Arc<SegQueue<Processed<A>>>,
+    reload_counters: Mutex<FnvHashMap<u32, Arc<AtomicUsize>>>,
     reloads: Vec<(WeakHandle<A>, Box<dyn Reload<A::Data>>)>,
+    residency: Mutex<Option<Residency<A>>>,
+    timings: FnvHashMap<u32, AssetTiming>,
     unused_handles: SegQueue<Handle<A>>,
+    waiting_trackers: Mutex<FnvHashMap<u32, Vec<Box<dyn Tracker>>>>,
+}
+
+/// Where an asset was loaded from, see `AssetStorage::origin`.
+struct Origin<A> {
+    source: String,
+    name: String,
+    format: Box<dyn Any + Send + Sync>,
+    handle: WeakHandle<A>,
+    requested: Instant,
+}
+
+/// The origins of assets by handle id, indexed by source and name.
+struct Origins<A> {
+    by_id: FnvHashMap<u32, Origin<A>>,
+    by_name: FnvHashMap<String, FnvHashMap<String, Vec<u32>>>,
+}
+
+impl<A> Default for Origins<A> {
+    fn default() -> Self {
+        Origins {
+            by_id: Default::default(),
+            by_name: Default::default(),
+        }
+    }
+}
+
+impl<A> Origins<A> {
+    fn get(&self, id: u32) -> Option<&Origin<A>> {
+        self.by_id.get(&id)
+    }
+
+    /// Returns the ids of the assets loaded from `name` in `source`.
+    fn find(&self, source: &str, name: &str) -> &[u32] {
+        self.by_name
+            .get(source)
+            .and_then(|names| names.get(name))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    fn insert(&mut self, id: u32, origin: Origin<A>) {
+        self.remove(id);
+        self.by_name
+            .entry(origin.source.clone())
+            .or_insert_with(Default::default)
+            .entry(origin.name.clone())
+            .or_insert_with(Vec::new)
+            .push(id);
+        self.by_id.insert(id, origin);
+    }
+
+    fn remove(&mut self, id: u32) -> Option<Origin<A>> {
+        let origin = self.by_id.remove(&id)?;
+        if let Some(names) = self.by_name.get_mut(&origin.source) {
+            if let Some(ids) = names.get_mut(&origin.name) {
+                ids.retain(|other| *other != id);
+                if ids.is_empty() {
+                    names.remove(&origin.name);
+                }
+            }
+            if names.is_empty() {
+                self.by_name.remove(&origin.source);
+            }
+        }
+        Some(origin)
+    }
+
+    fn clear(&mut self) {
+        self.by_id.clear();
+        self.by_name.clear();
+    }
 }
 
 /// Returned by processor systems, describes the loading state of the asset.
@@ -91,8 +180,281 @@ impl<A: Asset> AssetStorage<A> {
     /// Remove all data from asset storages, invalidating all associated handles.
     /// Trying to retreive any data using old handle will return `None`.
     pub fn unload_all(&mut self) {
+        for id in (&self.bitset).iter() {
+            self.events
+                .single_write(AssetEvent::Unloaded { handle_id: id });
+        }
         unsafe { self.assets.clean(&self.bitset) }
         self.bitset.clear();
+        self.dependencies.clear();
+        self.origins.get_mut().clear();
+        self.reload_counters.lock().clear();
+        self.timings.clear();
+    }
+
+    /// Registers a reader for the `AssetEvent`s of this storage.
+    pub fn register_event_reader(&mut self) -> ReaderId<AssetEvent<A>> {
+        self.events.register_reader()
+    }
+
+    /// Returns the channel of `AssetEvent`s emitted while this storage is processed.
+    ///
+    /// Read it with a reader registered with `register_event_reader`.
+    pub fn events(&self) -> &EventChannel<AssetEvent<A>> {
+        &self.events
+    }
+
+    /// Creates a `Dependency` on the asset behind `handle`, which can be registered
+    /// for an asset of another storage using `add_dependency`.
+    pub fn dependency(&self, handle: &Handle<A>) -> Dependency {
+        let reloads = self
+            .reload_counters
+            .lock()
+            .entry(handle.id())
+            .or_insert_with(Default::default)
+            .clone();
+
+        Dependency::new(A::NAME, handle.id(), reloads)
+    }
+
+    /// Registers a dependency of the asset behind `handle`.
+    ///
+    /// Once the dependency is reloaded, the asset will be reloaded as well, given that
+    /// it was loaded with hot reloading enabled.
+    /// Dependencies on assets which are loaded by the `Loader` while this asset is
+    /// processed are registered automatically, and replaced whenever it's processed again.
+    ///
+    /// ```rust,ignore
+    /// // reprocess the sprite sheet whenever its texture changes
+    /// let dependency = texture_storage.dependency(&texture_handle);
+    /// sprite_sheet_storage.add_dependency(&sprite_sheet_handle, dependency);
+    /// ```
+    pub fn add_dependency(&mut self, handle: &Handle<A>, dependency: Dependency) {
+        self.dependencies
+            .entry(handle.id())
+            .or_insert_with(Vec::new)
+            .push(dependency);
+    }
+
+    /// Returns the dependencies registered for the asset behind `handle`.
+    pub fn dependencies(&self, handle: &Handle<A>) -> &[Dependency] {
+        self.dependencies
+            .get(&handle.id())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Returns the source and name the asset behind `handle` was requested from the `Loader`
+    /// with, before resolving them with the manifest, or `None` if it was loaded from data or
+    /// inserted directly.
+    pub fn origin(&self, handle: &Handle<A>) -> Option<(String, String)> {
+        self.origins
+            .lock()
+            .get(handle.id())
+            .map(|origin| (origin.source.clone(), origin.name.clone()))
+    }
+
+    /// Returns a handle to the asset requested from `name` in `source` from the `Loader`,
+    /// if it's still alive.
+    pub fn loaded_from(&self, source: &str, name: &str) -> Option<Handle<A>> {
+        let origins = self.origins.lock();
+        origins
+            .find(source, name)
+            .iter()
+            .filter_map(|id| origins.get(*id))
+            .find_map(|origin| origin.handle.upgrade())
+    }
+
+    /// Returns the source, name and format the asset behind `handle` was loaded with,
+    /// if it was loaded with a format of type `F`, or `F` is `Box<dyn SerializableFormat<_>>`
+    /// and it was loaded with a format registered with `register_format`.
+    pub(crate) fn format_origin<F>(&self, handle: &Handle<A>) -> Option<(String, String, F)>
+    where
+        F: objekt::Clone + 'static,
+    {
+        self.origins.lock().get(handle.id()).and_then(|origin| {
+            let format: &dyn Any = &*origin.format;
+            format
+                .downcast_ref::<F>()
+                .map(|format| *objekt::clone_box(format))
+                .or_else(|| cast_format(format))
+                .map(|format| (origin.source.clone(), origin.name.clone(), format))
+        })
+    }
+
+    /// Returns the name and format the asset behind `handle` was loaded with from the
+    /// default source, if it was loaded with a format of type `F`.
+    pub(crate) fn file_origin<F>(&self, handle: &Handle<A>) -> Option<(String, F)>
+    where
+        F: objekt::Clone + 'static,
+    {
+        self.format_origin(handle)
+            .filter(|(source, _, _)| source.is_empty())
+            .map(|(_, name, format)| (name, format))
+    }
+
+    /// Records that the asset behind `handle` was requested from `name` in `source`.
+    pub(crate) fn set_origin<F>(&self, source: &str, name: &str, format: &F, handle: &Handle<A>)
+    where
+        F: objekt::Clone + Send + Sync + 'static,
+    {
+        self.origins.lock().insert(
+            handle.id(),
+            Origin {
+                source: source.to_owned(),
+                name: name.to_owned(),
+                format: objekt::clone_box(format),
+                handle: handle.downgrade(),
+                requested: Instant::now(),
+            },
+        );
+    }
+
+    /// Sets the memory budget of this storage in bytes, as reported by `Asset::memory_cost`.
+    ///
+    /// With a budget, assets loaded from a path stay resident after all handles to them
+    /// have been dropped, so loading the same path again returns the existing asset.
+    /// Once the storage exceeds its budget, the least recently used assets which are not
+    /// referenced anywhere else are unloaded; loading them again transparently reloads them.
+    /// Setting the budget to `None` (the default) disables this.
+    pub fn set_memory_budget(&mut self, budget: Option<usize>) {
+        let residency = self.residency.get_mut();
+        match (residency.as_mut(), budget) {
+            (Some(residency), Some(budget)) => residency.set_budget(budget),
+            (_, budget) => *residency = budget.map(Residency::new),
+        }
+    }
+
+    /// Returns the memory budget of this storage, see `set_memory_budget`.
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.residency.lock().as_ref().map(Residency::budget)
+    }
+
+    /// Returns the sum of `Asset::memory_cost` of all loaded assets.
+    pub fn memory_usage(&self) -> usize {
+        self.handles
+            .iter()
+            .filter_map(|handle| self.get(handle))
+            .map(A::memory_cost)
+            .sum()
+    }
+
+    /// Returns statistics about the assets of this storage.
+    ///
+    /// This walks all assets, see `AssetStats` for collecting them only when they're read.
+    pub fn stats(&self) -> AssetStorageStats {
+        let mut stats = AssetStorageStats {
+            asset_type_name: A::NAME,
+            num_pending: self.processed.len(),
+            bytes_loaded: self.load_stats.bytes(),
+            ..Default::default()
+        };
+        for handle in self.loaded_handles() {
+            stats.num_assets += 1;
+            if handle.is_unique() {
+                stats.num_unused += 1;
+            }
+            stats.memory_usage += self.get(handle).map_or(0, A::memory_cost);
+        }
+        stats
+    }
+
+    /// Returns how long it took to load the asset behind `handle`, if it was loaded by the
+    /// `Loader`.
+    pub fn timing(&self, handle: &Handle<A>) -> Option<&AssetTiming> {
+        self.timings.get(&handle.id())
+    }
+
+    /// Returns how long it took to load the assets loaded by the `Loader`, slowest asset first.
+    pub fn timings(&self) -> Vec<AssetTiming> {
+        let mut timings = self.timings.values().cloned().collect::<Vec<_>>();
+        timings.sort_by(|a, b| b.total.cmp(&a.total));
+
+        timings
+    }
+
+    fn loaded_handles(&self) -> impl Iterator<Item = &Handle<A>> {
+        self.handles
+            .iter()
+            .filter(move |handle| self.bitset.contains(handle.id()))
+    }
+
+    /// Returns the handle of a resident asset loaded from `name` in `source`.
+    pub(crate) fn resident(&self, source: &str, name: &str) -> Option<Handle<A>> {
+        self.residency
+            .lock()
+            .as_mut()
+            .and_then(|residency| residency.get(source, name))
+    }
+
+    /// Keeps the asset which is being loaded from `name` in `source` resident,
+    /// if this storage has a memory budget.
+    pub(crate) fn make_resident(&self, source: &str, name: &str, handle: &Handle<A>) {
+        if let Some(residency) = self.residency.lock().as_mut() {
+            residency.insert(source, name, handle.clone());
+        }
+    }
+
+    /// Reports the success of `tracker` once the asset behind `handle` is loaded, right away
+    /// if it is already.
+    pub(crate) fn track(&self, handle: &Handle<A>, tracker: Box<dyn Tracker>) {
+        if self.get(handle).is_some() {
+            tracker.success();
+        } else {
+            self.waiting_trackers
+                .lock()
+                .entry(handle.id())
+                .or_insert_with(Vec::new)
+                .push(tracker);
+        }
+    }
+
+    /// Unloads resident assets exceeding the memory budget.
+    fn evict(&mut self, frame_number: u64) {
+        if self.residency.get_mut().is_none() {
+            return;
+        }
+        let usage = self.memory_usage();
+        let assets = &self.assets;
+        let bitset = &self.bitset;
+        if let Some(residency) = self.residency.get_mut().as_mut() {
+            let evicted = residency.evict(usage, frame_number, |handle| {
+                if bitset.contains(handle.id()) {
+                    Some(unsafe { assets.get(handle.id()) }.0.memory_cost())
+                } else {
+                    None
+                }
+            });
+            if evicted != 0 {
+                debug!(
+                    "{:?}: Evicted {} assets exceeding the memory budget",
+                    A::NAME,
+                    evicted,
+                );
+            }
+        }
+    }
+
+    /// Notifies dependents of the asset with the given id that it has changed.
+    fn notify_dependents(&self, id: u32) {
+        if let Some(reloads) = self.reload_counters.lock().get(&id) {
+            reloads.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the ids of all assets with changed dependencies and drops dead dependencies.
+    fn changed_dependents(&mut self) -> BitSet {
+        let mut changed = BitSet::new();
+        self.dependencies.retain(|&id, dependencies| {
+            dependencies.retain(|dependency| !dependency.is_dead());
+            for dependency in dependencies.iter_mut() {
+                if dependency.poll_changed() {
+                    changed.add(id);
+                }
+            }
+            !dependencies.is_empty()
+        });
+        changed
     }
 
     /// When cloning an asset handle, you'll get another handle,
@@ -161,6 +523,7 @@ impl<A: Asset> AssetStorage<A> {
     /// Returns old asset. Panics if asset handle is empty.
     pub fn replace(&mut self, handle: &Handle<A>, asset: A) -> A {
         if self.bitset.contains(handle.id()) {
+            self.notify_dependents(handle.id());
             let data = unsafe { self.assets.get_mut(handle.id()) };
             data.1 += 1;
             std::mem::replace(&mut data.0, asset)
@@ -249,6 +612,7 @@ impl<A: Asset> AssetStorage<A> {
                 let bitset = &mut self.bitset;
                 let handles = &mut self.handles;
                 let reloads = &mut self.reloads;
+                let dependencies = &mut self.dependencies;
 
                 let f = &mut f;
                 let (reload_obj, handle) = match processed {
@@ -258,9 +622,14 @@ impl<A: Asset> AssetStorage<A> {
                         name,
                         tracker,
                     } => {
+                        let id = handle.id();
                         let (asset, reload_obj) = match data
                             .map(|FormatValue { data, reload }| (data, reload))
-                            .and_then(|(d, rel)| f(d).map(|a| (a, rel)))
+                            .and_then(|(d, rel)| {
+                                let (result, collected) = dependency::collect(|| f(d));
+                                replace_collected(dependencies, id, collected);
+                                result.map(|a| (a, rel))
+                            })
                             .with_context(|_| error::Error::Asset(name.clone()))
                         {
                             Ok((ProcessingState::Loaded(x), r)) => {
@@ -270,6 +639,26 @@ impl<A: Asset> AssetStorage<A> {
                                         name,
                                         handle,
                                     );
+                                let imported = self.load_stats.take_imported(id);
+                                let origin = self.origins.get_mut().get(id);
+                                if let (Some((bytes, imported)), Some(origin)) = (imported, origin)
+                                {
+                                    self.timings.insert(
+                                        id,
+                                        AssetTiming {
+                                            asset_type_name: A::NAME,
+                                            asset_name: name.clone(),
+                                            bytes,
+                                            read: imported.duration_since(origin.requested),
+                                            total: origin.requested.elapsed(),
+                                            failed: false,
+                                        },
+                                    );
+                                }
+                                self.events.single_write(AssetEvent::Loaded {
+                                    handle_id: id,
+                                    name: name.clone(),
+                                });
                                 // Add a warning if a handle is unique (i.e. asset does not
                                 // need to be loaded as it is not used by anything)
                                 // https://github.com/amethyst/amethyst/issues/628
@@ -287,6 +676,9 @@ impl<A: Asset> AssetStorage<A> {
                                 } else {
                                     tracker.success();
                                 }
+                                for tracker in take_waiting(self.waiting_trackers.get_mut(), id) {
+                                    tracker.success();
+                                }
 
                                 (x, r)
                             }
@@ -313,13 +705,31 @@ impl<A: Asset> AssetStorage<A> {
                                     handle,
                                     e,
                                 );
+                                self.events.single_write(AssetEvent::Failed {
+                                    handle_id: id,
+                                    name: name.clone(),
+                                    error: e.to_string(),
+                                });
+                                for waiting in take_waiting(self.waiting_trackers.get_mut(), id) {
+                                    waiting.fail(
+                                        id,
+                                        A::NAME,
+                                        name.clone(),
+                                        format_err!("Loading asset {:?} failed: {}", name, e),
+                                    );
+                                }
                                 tracker.fail(handle.id(), A::NAME, name, e);
+                                dependencies.remove(&id);
+                                self.origins.get_mut().remove(id);
+                                self.load_stats.take_imported(id);
+                                if let Some(residency) = self.residency.get_mut().as_mut() {
+                                    residency.remove(id);
+                                }
 
                                 continue;
                             }
                         };
 
-                        let id = handle.id();
                         bitset.add(id);
                         handles.push(handle.clone());
 
@@ -337,9 +747,14 @@ impl<A: Asset> AssetStorage<A> {
                         name,
                         old_reload,
                     } => {
+                        let id = handle.id();
                         let (asset, reload_obj) = match data
                             .map(|FormatValue { data, reload }| (data, reload))
-                            .and_then(|(d, rel)| f(d).map(|a| (a, rel)))
+                            .and_then(|(d, rel)| {
+                                let (result, collected) = dependency::collect(|| f(d));
+                                replace_collected(dependencies, id, collected);
+                                result.map(|a| (a, rel))
+                            })
                             .with_context(|_| error::Error::Asset(name.clone()))
                         {
                             Ok((ProcessingState::Loaded(x), r)) => (x, r),
@@ -367,6 +782,11 @@ impl<A: Asset> AssetStorage<A> {
                                     handle,
                                     e,
                                 );
+                                self.events.single_write(AssetEvent::Failed {
+                                    handle_id: id,
+                                    name,
+                                    error: e.to_string(),
+                                });
 
                                 reloads.push((handle.downgrade(), old_reload));
 
@@ -374,7 +794,6 @@ impl<A: Asset> AssetStorage<A> {
                             }
                         };
 
-                        let id = handle.id();
                         assert!(
                             bitset.contains(id),
                             "Expected handle {:?} to be valid, but the asset storage says otherwise",
@@ -383,9 +802,43 @@ impl<A: Asset> AssetStorage<A> {
                         let data = unsafe { self.assets.get_mut(id) };
                         data.1 += 1;
                         drop_fn(std::mem::replace(&mut data.0, asset));
+                        if let Some(reloads) = self.reload_counters.lock().get(&id) {
+                            reloads.fetch_add(1, Ordering::Relaxed);
+                        }
+                        self.events.single_write(AssetEvent::Reloaded {
+                            handle_id: id,
+                            name,
+                        });
 
                         (reload_obj, handle)
                     }
+                    Processed::Cancelled { handle } => {
+                        debug!(
+                            "{:?}: Loading asset with handle id {:?} was cancelled",
+                            A::NAME,
+                            handle,
+                        );
+                        if let Some(residency) = self.residency.get_mut().as_mut() {
+                            residency.remove(handle.id());
+                        }
+                        let name = self
+                            .origins
+                            .get_mut()
+                            .remove(handle.id())
+                            .map(|origin| origin.name)
+                            .unwrap_or_default();
+                        self.events.single_write(AssetEvent::Failed {
+                            handle_id: handle.id(),
+                            name: name.clone(),
+                            error: error::Error::Cancelled.to_string(),
+                        });
+                        for tracker in take_waiting(self.waiting_trackers.get_mut(), handle.id()) {
+                            tracker.cancel(handle.id(), A::NAME, name.clone());
+                        }
+                        self.load_stats.take_imported(handle.id());
+
+                        continue;
+                    }
                 };
 
                 // Add the reload obj if it is `Some`.
@@ -399,6 +852,8 @@ impl<A: Asset> AssetStorage<A> {
             }
         }
 
+        self.evict(frame_number);
+
         let mut count = 0;
         let mut skip = 0;
         while let Some(i) = self.handles.iter().skip(skip).position(Handle::is_unique) {
@@ -413,6 +868,12 @@ impl<A: Asset> AssetStorage<A> {
                 drop_fn(asset);
             }
             self.bitset.remove(id);
+            self.dependencies.remove(&id);
+            self.origins.get_mut().remove(id);
+            self.reload_counters.lock().remove(&id);
+            self.timings.remove(&id);
+            self.events
+                .single_write(AssetEvent::Unloaded { handle_id: id });
 
             // Can't reuse old handle here, because otherwise weak handles would still be valid.
             // TODO: maybe just store u32?
@@ -425,21 +886,37 @@ impl<A: Asset> AssetStorage<A> {
             debug!("{:?}: Freed {} handle ids", A::NAME, count,);
         }
 
-        if strategy
-            .map(|s| s.needs_reload(frame_number))
-            .unwrap_or(false)
-        {
+        let strategy = strategy.filter(|s| s.needs_reload(frame_number));
+        let changed_dependents = self.changed_dependents();
+        if strategy.is_some() || !changed_dependents.is_empty() {
             trace!("{:?}: Testing for asset reloads..", A::NAME);
-            self.hot_reload(pool);
+            self.hot_reload(pool, strategy, &changed_dependents);
         }
     }
 
-    fn hot_reload(&mut self, pool: &ThreadPool) {
+    /// Reloads assets which changed according to the given strategy, if any, and assets
+    /// with the given ids, whose dependencies have changed.
+    fn hot_reload(
+        &mut self,
+        pool: &ThreadPool,
+        strategy: Option<&HotReloadStrategy>,
+        changed_dependents: &BitSet,
+    ) {
+        let changed_paths = strategy.and_then(HotReloadStrategy::changed_paths);
+        let needs_reload = |handle: &WeakHandle<A>, rel: &dyn Reload<A::Data>| {
+            handle
+                .upgrade()
+                .map_or(false, |handle| changed_dependents.contains(handle.id()))
+                || (strategy.is_some()
+                    && changed_paths.map_or(true, |changed| changed.contains(&rel.name()))
+                    && rel.needs_reload())
+        };
+
         self.reloads.retain(|&(ref handle, _)| !handle.is_dead());
         while let Some(p) = self
             .reloads
             .iter()
-            .position(|&(_, ref rel)| rel.needs_reload())
+            .position(|&(ref handle, ref rel)| needs_reload(handle, &**rel))
         {
             let (handle, rel): (WeakHandle<_>, Box<dyn Reload<_>>) = self.reloads.swap_remove(p);
 
@@ -479,11 +956,19 @@ impl<A: Asset> Default for AssetStorage<A> {
         AssetStorage {
             assets: Default::default(),
             bitset: Default::default(),
+            dependencies: Default::default(),
+            events: Default::default(),
             handles: Default::default(),
             handle_alloc: Default::default(),
+            load_stats: Default::default(),
+            origins: Default::default(),
             processed: Arc::new(SegQueue::new()),
+            reload_counters: Default::default(),
             reloads: Default::default(),
+            residency: Mutex::new(None),
+            timings: Default::default(),
             unused_handles: SegQueue::new(),
+            waiting_trackers: Default::default(),
         }
     }
 }
@@ -535,9 +1020,10 @@ where
         ReadExpect<'a, Arc<ThreadPool>>,
         Read<'a, Time>,
         Option<Read<'a, HotReloadStrategy>>,
+        Read<'a, AssetStats>,
     );
 
-    fn run(&mut self, (mut storage, pool, time, strategy): Self::SystemData) {
+    fn run(&mut self, (mut storage, pool, time, strategy, stats): Self::SystemData) {
         #[cfg(feature = "profiler")]
         profile_scope!("processor_system");
 
@@ -547,6 +1033,7 @@ where
             &**pool,
             strategy.as_deref(),
         );
+        stats.update(&*storage);
     }
 }
 
@@ -587,6 +1074,11 @@ impl<A> Handle<A> {
     fn is_unique(&self) -> bool {
         Arc::strong_count(&self.id) == 1
     }
+
+    /// Returns the number of handles to the asset its pointing at.
+    pub(crate) fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.id)
+    }
 }
 
 impl<A> Component for Handle<A>
@@ -596,6 +1088,32 @@ where
     type Storage = A::HandleStorage;
 }
 
+/// Takes the trackers waiting for the asset with id `id`.
+fn take_waiting(
+    waiting_trackers: &mut FnvHashMap<u32, Vec<Box<dyn Tracker>>>,
+    id: u32,
+) -> Vec<Box<dyn Tracker>> {
+    waiting_trackers.remove(&id).unwrap_or_default()
+}
+
+/// Replaces the dependencies recorded while processing the asset with id `id` the last time,
+/// keeping the ones registered with `AssetStorage::add_dependency`.
+fn replace_collected(
+    dependencies: &mut FnvHashMap<u32, Vec<Dependency>>,
+    id: u32,
+    collected: Vec<Dependency>,
+) {
+    let empty = {
+        let entry = dependencies.entry(id).or_insert_with(Vec::new);
+        entry.retain(|dependency| !dependency.collected);
+        entry.extend(collected);
+        entry.is_empty()
+    };
+    if empty {
+        dependencies.remove(&id);
+    }
+}
+
 pub(crate) enum Processed<A: Asset> {
     NewAsset {
         data: Result<FormatValue<A::Data>, Error>,
@@ -609,6 +1127,9 @@ pub(crate) enum Processed<A: Asset> {
         name: String,
         old_reload: Box<dyn Reload<A::Data>>,
     },
+    Cancelled {
+        handle: Handle<A>,
+    },
 }
 
 /// A weak handle, which is useful if you don't directly need the asset
@@ -0,0 +1,61 @@
+//! Fixtures shared by the tests of this crate.
+
+use std::{thread, time::Duration};
+
+use serde::Deserialize;
+
+use amethyst_core::ecs::VecStorage;
+use amethyst_error::Error;
+
+use crate::{Asset, AssetStorage, Handle, ProcessableAsset, Source, ThreadPool};
+
+/// Asset without any contents, loaded from an empty RON value.
+#[derive(Debug, Deserialize)]
+pub struct Level;
+
+impl Asset for Level {
+    const NAME: &'static str = "Level";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+/// Source serving an empty RON value for every path.
+pub struct UnitSource;
+
+impl Source for UnitSource {
+    fn modified(&self, _: &str) -> Result<u64, Error> {
+        Ok(0)
+    }
+
+    fn load(&self, _: &str) -> Result<Vec<u8>, Error> {
+        Ok(b"()".to_vec())
+    }
+}
+
+/// Runs `frame` for consecutive frame numbers starting at `first_frame` until it returns `true`,
+/// giving the loading threads some time between the frames.
+///
+/// Returns the frame number `frame` returned `true` for and panics if it doesn't within 100 frames.
+pub fn run_until(first_frame: u64, mut frame: impl FnMut(u64) -> bool) -> u64 {
+    for frame_number in first_frame..first_frame + 100 {
+        if frame(frame_number) {
+            return frame_number;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("Condition was not met within 100 frames");
+}
+
+/// Processes `storage` from frame 0 on until `done` returns `true` for it.
+///
+/// Panics if that doesn't happen within 100 frames.
+pub fn process_until<A: ProcessableAsset>(
+    storage: &mut AssetStorage<A>,
+    pool: &ThreadPool,
+    mut done: impl FnMut(&AssetStorage<A>) -> bool,
+) {
+    run_until(0, |frame_number| {
+        storage.process(ProcessableAsset::process, frame_number, pool, None);
+        done(storage)
+    });
+}
@@ -0,0 +1,56 @@
+//! Cooks an asset folder into an import cache, see `amethyst_assets::ImportCache`.
+//!
+//! Usage: `amethyst_cook <asset directory> [cache directory]`
+
+#![warn(missing_docs, rust_2018_idioms, rust_2018_compatibility)]
+
+use std::{env, process};
+
+use amethyst_assets::{Cooker, ImportCache};
+use amethyst_error::Error;
+use amethyst_rendy::formats::{mesh::ObjFormat, texture::ImageFormat};
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "tga", "gif"];
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (assets, cache) = match args.as_slice() {
+        [assets] => (assets.as_str(), "cache"),
+        [assets, cache] => (assets.as_str(), cache.as_str()),
+        _ => {
+            eprintln!("Usage: amethyst_cook <asset directory> [cache directory]");
+            process::exit(2);
+        }
+    };
+
+    match cook(assets, cache) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("Failed to cook {:?}: {}", assets, e);
+            process::exit(1);
+        }
+    }
+}
+
+/// Cooks all assets, returning `false` if any asset failed.
+fn cook(assets: &str, cache: &str) -> Result<bool, Error> {
+    let mut cooker = Cooker::new(ImportCache::new(cache)).with_format("obj", ObjFormat);
+    for extension in IMAGE_EXTENSIONS {
+        cooker = cooker.with_format(extension, ImageFormat::default());
+    }
+
+    let report = cooker.cook_directory(assets)?;
+    for (name, e) in &report.failed {
+        eprintln!("Failed to cook {:?}: {}", name, e);
+    }
+    println!(
+        "Cooked {} assets, {} up to date, {} skipped, {} failed",
+        report.cooked.len(),
+        report.up_to_date.len(),
+        report.skipped.len(),
+        report.failed.len(),
+    );
+
+    Ok(report.failed.is_empty())
+}
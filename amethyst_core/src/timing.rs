@@ -1,6 +1,9 @@
 //! Utilities for working with time.
 
-use std::time::{Duration, Instant};
+use std::{
+    num::NonZeroU32,
+    time::{Duration, Instant},
+};
 
 /// Frame timing values.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -19,6 +22,8 @@ pub struct Time {
     fixed_time: Duration,
     /// The total number of frames that have been played in this session.
     frame_number: u64,
+    /// The total number of fixed updates that have been run in this session.
+    fixed_frame_number: u64,
     ///Time elapsed since game start, ignoring the speed multipler.
     absolute_real_time: Duration,
     ///Time elapsed since game start, taking the speed multiplier into account.
@@ -29,6 +34,37 @@ pub struct Time {
     fixed_time_accumulator: f32,
     /// Fixed update interpolation alpha
     interpolation_alpha: f32,
+    /// Maximum number of fixed updates per frame.
+    max_fixed_steps: Option<NonZeroU32>,
+    /// What happens to the time of fixed updates exceeding `max_fixed_steps`.
+    fixed_step_overflow: FixedStepOverflow,
+    /// The number of fixed updates run in the current frame.
+    fixed_steps: u32,
+    /// Time dropped in the current frame because of `max_fixed_steps`.
+    dropped_time: Duration,
+    /// Time dropped since game start because of `max_fixed_steps`.
+    total_dropped_time: Duration,
+}
+
+/// What happens when more fixed updates are due in a frame than `Time::max_fixed_steps`
+/// allows, e.g. after a long hitch caused by loading assets or pausing in a debugger.
+///
+/// Without a limit, catching up takes so long that even more fixed updates are due in the next
+/// frame, and the game never recovers (the "spiral of death").
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixedStepOverflow {
+    /// The time of the excess fixed updates is dropped, so the simulation skips ahead. The
+    /// dropped time is reported by `Time::dropped_time`.
+    Drop,
+    /// The time of the excess fixed updates is kept and caught up in later frames, so the
+    /// simulation slows down until it caught up.
+    Slowdown,
+}
+
+impl Default for FixedStepOverflow {
+    fn default() -> Self {
+        FixedStepOverflow::Drop
+    }
 }
 
 impl Time {
@@ -73,6 +109,11 @@ impl Time {
         self.frame_number
     }
 
+    /// Gets the number of fixed updates run so far.  This increments by 1 with every fixed update.
+    pub fn fixed_frame_number(&self) -> u64 {
+        self.fixed_frame_number
+    }
+
     /// Gets the time since the start of the game, taking into account the speed multiplier.
     pub fn absolute_time(&self) -> Duration {
         self.absolute_time
@@ -103,6 +144,33 @@ impl Time {
         self.interpolation_alpha
     }
 
+    /// Gets the maximum number of fixed updates run per frame, `None` if there is no limit.
+    pub fn max_fixed_steps(&self) -> Option<NonZeroU32> {
+        self.max_fixed_steps
+    }
+
+    /// Gets what happens to the time of fixed updates exceeding `max_fixed_steps`.
+    pub fn fixed_step_overflow(&self) -> FixedStepOverflow {
+        self.fixed_step_overflow
+    }
+
+    /// Gets the number of fixed updates run in the current frame.
+    pub fn fixed_steps(&self) -> u32 {
+        self.fixed_steps
+    }
+
+    /// Gets the time dropped in the current frame because more fixed updates were due than
+    /// `max_fixed_steps` allows.
+    pub fn dropped_time(&self) -> Duration {
+        self.dropped_time
+    }
+
+    /// Gets the time dropped since game start because more fixed updates were due than
+    /// `max_fixed_steps` allows.
+    pub fn total_dropped_time(&self) -> Duration {
+        self.total_dropped_time
+    }
+
     /// Sets both `delta_seconds` and `delta_time` based on the seconds given.
     ///
     /// This should only be called by the engine.  Bad things might happen if you call this in
@@ -143,6 +211,17 @@ impl Time {
         self.fixed_time = time;
     }
 
+    /// Limits the number of fixed updates run per frame to `max_steps`, or removes the limit if
+    /// `None`. The time of fixed updates exceeding the limit is handled according to `overflow`.
+    pub fn set_max_fixed_steps(
+        &mut self,
+        max_steps: Option<NonZeroU32>,
+        overflow: FixedStepOverflow,
+    ) {
+        self.max_fixed_steps = max_steps;
+        self.fixed_step_overflow = overflow;
+    }
+
     /// Increments the current frame number by 1.
     ///
     /// This should only be called by the engine.  Bad things might happen if you call this in
@@ -169,6 +248,8 @@ impl Time {
     /// your game.
     pub fn start_fixed_update(&mut self) {
         self.fixed_time_accumulator += self.delta_real_seconds;
+        self.fixed_steps = 0;
+        self.dropped_time = Duration::from_secs(0);
     }
 
     /// Checks to see if we should perform another fixed update iteration, and if so, returns true
@@ -177,12 +258,26 @@ impl Time {
     /// This should only be called by the engine.  Bad things might happen if you call this in
     /// your game.
     pub fn step_fixed_update(&mut self) -> bool {
-        if self.fixed_time_accumulator >= self.fixed_seconds {
-            self.fixed_time_accumulator -= self.fixed_seconds;
-            true
-        } else {
-            false
+        if self.fixed_time_accumulator < self.fixed_seconds {
+            return false;
+        }
+        if self
+            .max_fixed_steps
+            .map_or(false, |max| self.fixed_steps >= max.get())
+        {
+            if self.fixed_step_overflow == FixedStepOverflow::Drop {
+                let remainder = self.fixed_time_accumulator % self.fixed_seconds;
+                let dropped = secs_to_duration(self.fixed_time_accumulator - remainder);
+                self.fixed_time_accumulator = remainder;
+                self.dropped_time += dropped;
+                self.total_dropped_time += dropped;
+            }
+            return false;
         }
+        self.fixed_time_accumulator -= self.fixed_seconds;
+        self.fixed_frame_number += 1;
+        self.fixed_steps += 1;
+        true
     }
 
     /// Updates the interpolation alpha factor given the current fixed update rate and accumulator.
@@ -190,7 +285,8 @@ impl Time {
     /// This should only be called by the engine.  Bad things might happen if you call this in
     /// your game.
     pub fn finish_fixed_update(&mut self) {
-        self.interpolation_alpha = self.fixed_time_accumulator / self.fixed_seconds;
+        // Time carried over with `FixedStepOverflow::Slowdown` can exceed a fixed update.
+        self.interpolation_alpha = (self.fixed_time_accumulator / self.fixed_seconds).min(1.0);
     }
 }
 
@@ -205,7 +301,13 @@ impl Default for Time {
             fixed_time: Duration::new(0, 16_666_666),
             fixed_time_accumulator: 0.0,
             frame_number: 0,
+            fixed_frame_number: 0,
             interpolation_alpha: 0.0,
+            max_fixed_steps: None,
+            fixed_step_overflow: FixedStepOverflow::Drop,
+            fixed_steps: 0,
+            dropped_time: Duration::from_secs(0),
+            total_dropped_time: Duration::from_secs(0),
             absolute_real_time: Duration::default(),
             absolute_time: Duration::default(),
             time_scale: 1.0,
@@ -406,6 +508,7 @@ mod tests {
         }
 
         assert_eq!(fixed_count, 120);
+        assert_eq!(time.fixed_frame_number(), 120);
     }
 
     // Test that fixed_update methods accumulate and return correctly
@@ -432,6 +535,45 @@ mod tests {
         }
         assert_eq!(fixed_count, 2);
     }
+
+    // Test that a hitch of one second only runs the maximum number of fixed updates, and that
+    // the rest of the time is dropped or caught up in later frames depending on the policy.
+    #[test]
+    fn fixed_update_max_steps() {
+        use super::{FixedStepOverflow, Time};
+        use approx::assert_ulps_eq;
+        use std::num::NonZeroU32;
+
+        for &overflow in &[FixedStepOverflow::Drop, FixedStepOverflow::Slowdown] {
+            let mut time = Time::default();
+            time.set_fixed_seconds(0.125);
+            time.set_max_fixed_steps(NonZeroU32::new(2), overflow);
+
+            let mut fixed_counts = Vec::new();
+            for &step in &[1.0, 0.0, 0.0] {
+                time.set_delta_seconds(step);
+                time.start_fixed_update();
+                let mut fixed_count = 0;
+                while time.step_fixed_update() {
+                    fixed_count += 1;
+                }
+                time.finish_fixed_update();
+                fixed_counts.push(fixed_count);
+            }
+
+            match overflow {
+                FixedStepOverflow::Drop => {
+                    assert_eq!(vec![2, 0, 0], fixed_counts);
+                    assert_eq!(Duration::from_millis(750), time.total_dropped_time());
+                }
+                FixedStepOverflow::Slowdown => {
+                    assert_eq!(vec![2, 2, 2], fixed_counts);
+                    assert_eq!(Duration::from_secs(0), time.total_dropped_time());
+                    assert_ulps_eq!(1.0, time.interpolation_alpha());
+                }
+            }
+        }
+    }
 }
 
 /// Converts a Duration to the time in seconds.
@@ -12,8 +12,10 @@ use crate::{
 
 /// Transform bundle
 ///
-/// Will register transform components, and the `TransformSystem`.
-/// `TransformSystem` will be registered with name "transform_system".
+/// Will register transform components, the `TransformSystem` and the
+/// `TransformInterpolationSystem`.
+/// `TransformSystem` will be registered with name "transform_system", and
+/// `TransformInterpolationSystem` with name "transform_interpolation_system".
 ///
 /// ## Errors
 ///
@@ -59,6 +61,11 @@ impl<'a, 'b, 'c> SystemBundle<'a, 'b> for TransformBundle<'c> {
             "transform_system",
             &["parent_hierarchy_system"],
         );
+        builder.add(
+            TransformInterpolationSystem,
+            "transform_interpolation_system",
+            &["transform_system"],
+        );
         Ok(())
     }
 }
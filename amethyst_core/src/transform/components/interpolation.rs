@@ -0,0 +1,122 @@
+//! Interpolation of transforms between fixed updates.
+
+use crate::{
+    ecs::prelude::{Component, DenseVecStorage},
+    math::{self as na, Matrix4},
+    transform::Transform,
+};
+
+/// Smooths the rendered motion of an entity moved during `fixed_update`.
+///
+/// The `TransformInterpolationSystem` records the local `Transform` of the last two fixed
+/// updates and computes a global matrix interpolated between them by
+/// `Time::interpolation_alpha`, which the renderer uses instead of `Transform::global_matrix`,
+/// see `render_matrix`. The rendered transform lags one fixed update behind the simulation,
+/// which is left unchanged.
+///
+/// The transform is only recorded once per frame, so if several fixed updates ran in one
+/// frame, the motion is assumed to be linear across them and only their last part is
+/// interpolated.
+///
+/// The system adds an `InterpolatedTransform` to all descendants of an entity which has one,
+/// so children move along with their smoothly moving parent.
+///
+/// Changes to the `Transform` outside of fixed updates are applied without interpolation.
+/// Call `reset` after teleporting an entity during a fixed update to do the same.
+#[derive(Clone, Debug)]
+pub struct InterpolatedTransform {
+    previous: Option<Transform>,
+    current: Option<Transform>,
+    fixed_frame_number: u64,
+    /// Number of fixed updates between the previous and current transform.
+    steps: u64,
+    pub(crate) global_matrix: Matrix4<f32>,
+}
+
+impl InterpolatedTransform {
+    /// Creates a new interpolated transform, which starts interpolating after the next fixed
+    /// update.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the interpolated global transformation matrix.
+    pub fn global_matrix(&self) -> &Matrix4<f32> {
+        &self.global_matrix
+    }
+
+    /// Returns the inverse of the interpolated global matrix, like `Transform::global_view_matrix`.
+    pub fn global_view_matrix(&self) -> Matrix4<f32> {
+        self.global_matrix
+            .try_inverse()
+            .expect("Interpolated global matrix is not invertible")
+    }
+
+    /// Returns the global matrix to render an entity with: the interpolated one if it has an
+    /// `InterpolatedTransform`, and `Transform::global_matrix` otherwise.
+    pub fn render_matrix<'a>(
+        interpolated: Option<&'a InterpolatedTransform>,
+        transform: &'a Transform,
+    ) -> &'a Matrix4<f32> {
+        interpolated.map_or_else(|| transform.global_matrix(), |i| i.global_matrix())
+    }
+
+    /// Stops interpolating from the transform of the previous fixed update, so the next
+    /// change of the `Transform` is applied right away.
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.current = None;
+    }
+
+    /// Records `transform` if a fixed update ran since the last call, or if it was changed
+    /// outside of fixed updates.
+    pub(crate) fn update(&mut self, transform: &Transform, fixed_frame_number: u64) {
+        match self.current.take() {
+            Some(current) if fixed_frame_number != self.fixed_frame_number => {
+                self.previous = Some(current);
+                self.steps = fixed_frame_number - self.fixed_frame_number;
+            }
+            Some(ref current)
+                if current.isometry() == transform.isometry()
+                    && current.scale() == transform.scale() => {}
+            _ => self.previous = None,
+        }
+        self.current = Some(transform.clone());
+        self.fixed_frame_number = fixed_frame_number;
+    }
+
+    /// Returns the local matrix interpolated between the previous and current transform.
+    pub(crate) fn local_matrix(&self, alpha: f32) -> Matrix4<f32> {
+        match (&self.previous, &self.current) {
+            (Some(previous), Some(current)) => {
+                // only interpolate over the last of several fixed updates
+                let steps = self.steps.max(1) as f32;
+                let alpha = (steps - 1.0 + alpha) / steps;
+                let mut interpolated = current.clone();
+                *interpolated.translation_mut() =
+                    previous.translation().lerp(current.translation(), alpha);
+                *interpolated.rotation_mut() = previous.rotation().slerp(current.rotation(), alpha);
+                *interpolated.scale_mut() = previous.scale().lerp(current.scale(), alpha);
+                interpolated.matrix()
+            }
+            (None, Some(current)) => current.matrix(),
+            _ => na::one(),
+        }
+    }
+}
+
+impl Default for InterpolatedTransform {
+    fn default() -> Self {
+        InterpolatedTransform {
+            previous: None,
+            current: None,
+            fixed_frame_number: 0,
+            steps: 1,
+            global_matrix: na::one(),
+        }
+    }
+}
+
+impl Component for InterpolatedTransform {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,339 @@
+//! Accessing and modifying transforms in world space.
+
+use amethyst_error::{format_err, Error};
+
+use crate::{
+    ecs::{
+        hibitset::BitSet,
+        prelude::{Entity, World, WriteStorage},
+        shred::{ResourceId, SystemData},
+    },
+    math::{self as na, Matrix4, Point3, Rotation3, UnitQuaternion, Vector3, U3},
+    transform::{Parent, Transform},
+};
+
+/// Utility `SystemData` reading and writing the world-space ("global") translation, rotation
+/// and scale of entities, taking their `Parent`s into account.
+///
+/// Unlike `Transform::global_matrix`, which is only updated by the `TransformSystem`, the
+/// global transforms returned here are computed from the local `Transform`s of the entity and
+/// its ancestors, so they include changes made earlier in the same frame.
+///
+/// Rotations and scales are only exact if no ancestor is both rotated and scaled
+/// non-uniformly, as the shear this results in can't be represented by a `Transform`.
+///
+/// ```rust,ignore
+/// // Pick up an item, keeping it where it is in the world.
+/// transforms.reparent(item, Some(hand))?;
+/// // Drop it again.
+/// transforms.reparent(item, None)?;
+/// ```
+#[derive(SystemData)]
+#[allow(missing_debug_implementations)]
+pub struct GlobalTransforms<'a> {
+    transforms: WriteStorage<'a, Transform>,
+    parents: WriteStorage<'a, Parent>,
+}
+
+impl<'a> GlobalTransforms<'a> {
+    /// Returns the global transformation matrix of `entity`, or `None` if it has no
+    /// `Transform`.
+    pub fn global_matrix(&self, entity: Entity) -> Option<Matrix4<f32>> {
+        let local = self.transforms.get(entity)?;
+        Some(self.parent_matrix(entity) * local.matrix())
+    }
+
+    /// Returns the global translation of `entity`, or `None` if it has no `Transform`.
+    pub fn global_translation(&self, entity: Entity) -> Option<Vector3<f32>> {
+        self.global_matrix(entity)
+            .map(|matrix| matrix.column(3).xyz())
+    }
+
+    /// Returns the global rotation of `entity`, or `None` if it has no `Transform`.
+    pub fn global_rotation(&self, entity: Entity) -> Option<UnitQuaternion<f32>> {
+        self.global_matrix(entity)
+            .map(|matrix| decompose(&matrix).1)
+    }
+
+    /// Returns the global scale of `entity`, or `None` if it has no `Transform`.
+    pub fn global_scale(&self, entity: Entity) -> Option<Vector3<f32>> {
+        self.global_matrix(entity)
+            .map(|matrix| decompose(&matrix).2)
+    }
+
+    /// Changes the local `Transform` of `entity` so its global transformation matrix becomes
+    /// `matrix`.
+    pub fn set_global_matrix(&mut self, entity: Entity, matrix: Matrix4<f32>) -> Result<(), Error> {
+        let local = self.parent_inverse(entity, self.parent_matrix(entity))? * matrix;
+        self.set_local_matrix(entity, &local)
+    }
+
+    /// Changes the local translation of `entity` so its global translation becomes
+    /// `translation`.
+    pub fn set_global_translation(
+        &mut self,
+        entity: Entity,
+        translation: Vector3<f32>,
+    ) -> Result<(), Error> {
+        let inverse = self.parent_inverse(entity, self.parent_matrix(entity))?;
+        let local = inverse.transform_point(&Point3::from(translation));
+        *self
+            .transforms
+            .get_mut(entity)
+            .ok_or_else(|| no_transform(entity))?
+            .translation_mut() = local.coords;
+        Ok(())
+    }
+
+    /// Changes the local rotation and scale of `entity` so its global rotation becomes
+    /// `rotation`, keeping its global translation and scale.
+    pub fn set_global_rotation(
+        &mut self,
+        entity: Entity,
+        rotation: UnitQuaternion<f32>,
+    ) -> Result<(), Error> {
+        let global = self
+            .global_matrix(entity)
+            .ok_or_else(|| no_transform(entity))?;
+        let (translation, _, scale) = decompose(&global);
+        self.set_global_matrix(entity, compose(&translation, &rotation, &scale))
+    }
+
+    /// Changes the local rotation and scale of `entity` so its global scale becomes `scale`,
+    /// keeping its global translation and rotation.
+    pub fn set_global_scale(&mut self, entity: Entity, scale: Vector3<f32>) -> Result<(), Error> {
+        let global = self
+            .global_matrix(entity)
+            .ok_or_else(|| no_transform(entity))?;
+        let (translation, rotation, _) = decompose(&global);
+        self.set_global_matrix(entity, compose(&translation, &rotation, &scale))
+    }
+
+    /// Makes `parent` the new parent of `entity`, or removes its parent if `None`, and changes
+    /// its local `Transform` so it keeps its global transform.
+    ///
+    /// Fails without changing anything if `entity` has no `Transform`, or if `parent` is
+    /// `entity` itself or one of its descendants.
+    pub fn reparent(&mut self, entity: Entity, parent: Option<Entity>) -> Result<(), Error> {
+        let global = self
+            .global_matrix(entity)
+            .ok_or_else(|| no_transform(entity))?;
+        let parent_matrix = match parent {
+            Some(parent) => {
+                if parent == entity || self.ancestors(parent).any(|ancestor| ancestor == entity) {
+                    return Err(format_err!(
+                        "Can't make {:?} the parent of its ancestor {:?}",
+                        parent,
+                        entity
+                    ));
+                }
+                self.global_matrix(parent).unwrap_or_else(na::one)
+            }
+            None => na::one(),
+        };
+        let local = self.parent_inverse(entity, parent_matrix)? * global;
+
+        match parent {
+            Some(parent) => {
+                self.parents
+                    .insert(entity, Parent::new(parent))
+                    .map_err(|_| format_err!("Can't set the parent of dead entity {:?}", entity))?;
+            }
+            None => {
+                self.parents.remove(entity);
+            }
+        }
+        self.set_local_matrix(entity, &local)
+    }
+
+    /// Returns the global matrix of the parent of `entity`, which is the identity if it has
+    /// no parent or the parent has no `Transform`, like in the `TransformSystem`.
+    fn parent_matrix(&self, entity: Entity) -> Matrix4<f32> {
+        let mut matrix = na::one::<Matrix4<f32>>();
+        for ancestor in self.ancestors(entity) {
+            match self.transforms.get(ancestor) {
+                Some(transform) => matrix = transform.matrix() * matrix,
+                None => break,
+            }
+        }
+        matrix
+    }
+
+    /// Iterates over the ancestors of `entity`, starting with its parent and stopping before
+    /// any entity is visited twice if the `Parent`s form a cycle.
+    fn ancestors(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        let mut visited = BitSet::new();
+        visited.add(entity.id());
+        let mut current = entity;
+        std::iter::from_fn(move || {
+            let parent = self.parents.get(current)?.entity;
+            if visited.add(parent.id()) {
+                return None;
+            }
+            current = parent;
+            Some(parent)
+        })
+    }
+
+    fn parent_inverse(
+        &self,
+        entity: Entity,
+        parent_matrix: Matrix4<f32>,
+    ) -> Result<Matrix4<f32>, Error> {
+        parent_matrix.try_inverse().ok_or_else(|| {
+            format_err!(
+                "The global transform of the parent of {:?} isn't invertible",
+                entity
+            )
+        })
+    }
+
+    fn set_local_matrix(&mut self, entity: Entity, matrix: &Matrix4<f32>) -> Result<(), Error> {
+        let (translation, rotation, scale) = decompose(matrix);
+        let transform = self
+            .transforms
+            .get_mut(entity)
+            .ok_or_else(|| no_transform(entity))?;
+        *transform.translation_mut() = translation;
+        *transform.rotation_mut() = rotation;
+        *transform.scale_mut() = scale;
+        Ok(())
+    }
+}
+
+fn no_transform(entity: Entity) -> Error {
+    format_err!("Entity {:?} has no Transform", entity)
+}
+
+fn compose(
+    translation: &Vector3<f32>,
+    rotation: &UnitQuaternion<f32>,
+    scale: &Vector3<f32>,
+) -> Matrix4<f32> {
+    rotation
+        .to_homogeneous()
+        .prepend_nonuniform_scaling(scale)
+        .append_translation(translation)
+}
+
+/// Splits an affine transformation matrix into translation, rotation and scale.
+fn decompose(matrix: &Matrix4<f32>) -> (Vector3<f32>, UnitQuaternion<f32>, Vector3<f32>) {
+    let translation = matrix.column(3).xyz();
+    let mut basis = matrix.fixed_slice::<U3, U3>(0, 0).into_owned();
+    let mut scale = Vector3::new(
+        basis.column(0).norm(),
+        basis.column(1).norm(),
+        basis.column(2).norm(),
+    );
+    // A mirroring is represented by a negative scale.
+    if basis.determinant() < 0.0 {
+        scale.x = -scale.x;
+    }
+    for i in 0..3 {
+        if scale[i] != 0.0 {
+            let column = basis.column(i) / scale[i];
+            basis.set_column(i, &column);
+        }
+    }
+    let rotation = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(basis));
+
+    (translation, rotation, scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use crate::ecs::prelude::{Builder, WorldExt};
+
+    use super::*;
+
+    #[test]
+    fn reparenting_keeps_global_transform() {
+        let mut world = World::new();
+        world.register::<Transform>();
+        world.register::<Parent>();
+
+        let mut hand = Transform::default();
+        hand.set_translation_xyz(1.0, 2.0, 0.0);
+        hand.set_rotation_z_axis(std::f32::consts::FRAC_PI_2);
+        hand.set_scale(Vector3::new(2.0, 2.0, 2.0));
+        let hand = world.create_entity().with(hand).build();
+        let mut item = Transform::default();
+        item.set_translation_xyz(3.0, 0.0, 0.0);
+        let item = world.create_entity().with(item).build();
+
+        let mut transforms = world.system_data::<GlobalTransforms<'_>>();
+        transforms.reparent(item, Some(hand)).unwrap();
+        assert_relative_eq!(
+            Vector3::new(3.0, 0.0, 0.0),
+            transforms.global_translation(item).unwrap(),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            Vector3::new(1.0, 1.0, 1.0),
+            transforms.global_scale(item).unwrap(),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            Vector3::new(0.5, 0.5, 0.5),
+            *transforms.transforms.get(item).unwrap().scale(),
+            epsilon = 1e-5
+        );
+        assert!(transforms.reparent(hand, Some(item)).is_err());
+
+        transforms
+            .set_global_rotation(item, UnitQuaternion::identity())
+            .unwrap();
+        transforms
+            .set_global_translation(item, Vector3::new(0.0, 0.0, 1.0))
+            .unwrap();
+        transforms.reparent(item, None).unwrap();
+        let local = transforms.transforms.get(item).unwrap();
+        assert_relative_eq!(
+            Vector3::new(0.0, 0.0, 1.0),
+            *local.translation(),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            UnitQuaternion::identity(),
+            *local.rotation(),
+            epsilon = 1e-5
+        );
+    }
+
+    #[test]
+    fn parent_cycles_are_not_followed() {
+        let mut world = World::new();
+        world.register::<Transform>();
+        world.register::<Parent>();
+
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(1.0, 0.0, 0.0);
+        let a = world.create_entity().with(transform.clone()).build();
+        let b = world
+            .create_entity()
+            .with(transform.clone())
+            .with(Parent::new(a))
+            .build();
+        world
+            .write_storage::<Parent>()
+            .insert(a, Parent::new(b))
+            .unwrap();
+        let c = world.create_entity().with(transform).build();
+
+        let mut transforms = world.system_data::<GlobalTransforms<'_>>();
+        assert_relative_eq!(
+            Vector3::new(2.0, 0.0, 0.0),
+            transforms.global_translation(a).unwrap(),
+            epsilon = 1e-5
+        );
+        assert!(transforms.reparent(a, Some(b)).is_err());
+        transforms.reparent(c, Some(a)).unwrap();
+        assert_relative_eq!(
+            Vector3::new(1.0, 0.0, 0.0),
+            transforms.global_translation(c).unwrap(),
+            epsilon = 1e-5
+        );
+    }
+}
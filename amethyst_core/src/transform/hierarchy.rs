@@ -0,0 +1,287 @@
+//! Queries over the `Parent` hierarchy.
+
+use std::collections::VecDeque;
+
+use amethyst_error::{format_err, Error};
+
+use crate::{
+    ecs::{
+        prelude::{Builder, Entities, Entity, LazyUpdate, ReadExpect, ReadStorage, World},
+        shred::{ResourceId, SystemData},
+        world::LazyBuilder,
+    },
+    transform::{Parent, ParentHierarchy},
+    Named,
+};
+
+/// Utility `SystemData` for navigating the hierarchy built from `Parent` components.
+///
+/// The hierarchy is maintained by the "parent_hierarchy_system" of the `TransformBundle`, so
+/// changes to `Parent` components are only visible after it ran.
+///
+/// ```rust,ignore
+/// let weapon = hierarchy.find(player, "arm/hand/weapon");
+/// for ancestor in hierarchy.ancestors(weapon) { /* ... */ }
+/// ```
+#[derive(SystemData)]
+#[allow(missing_debug_implementations)]
+pub struct HierarchyQuery<'a> {
+    entities: Entities<'a>,
+    hierarchy: ReadExpect<'a, ParentHierarchy>,
+    names: ReadStorage<'a, Named>,
+}
+
+impl<'a> HierarchyQuery<'a> {
+    /// Returns the parent of `entity`, if it has one.
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.hierarchy.parent(entity)
+    }
+
+    /// Returns the direct children of `entity`.
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        self.hierarchy.children(entity)
+    }
+
+    /// Iterates over the descendants of `entity` depth-first, visiting every entity before
+    /// its children.
+    pub fn descendants(&self, entity: Entity) -> DepthFirst<'_> {
+        DepthFirst {
+            hierarchy: &*self.hierarchy,
+            stack: self.children(entity).iter().rev().cloned().collect(),
+        }
+    }
+
+    /// Iterates over the descendants of `entity` breadth-first, visiting all children before
+    /// any grandchildren.
+    pub fn descendants_breadth_first(&self, entity: Entity) -> BreadthFirst<'_> {
+        BreadthFirst {
+            hierarchy: &*self.hierarchy,
+            queue: self.children(entity).iter().cloned().collect(),
+        }
+    }
+
+    /// Iterates over the ancestors of `entity`, starting with its parent.
+    pub fn ancestors(&self, entity: Entity) -> Ancestors<'_> {
+        Ancestors {
+            hierarchy: &*self.hierarchy,
+            entity,
+        }
+    }
+
+    /// Returns the topmost ancestor of `entity`, or `entity` itself if it has no parent.
+    pub fn root(&self, entity: Entity) -> Entity {
+        self.ancestors(entity).last().unwrap_or(entity)
+    }
+
+    /// Finds the descendant of `entity` at `path`, a `/` separated list of the `Named` names of
+    /// the entities leading to it, e.g. `"arm/hand/weapon"`.
+    ///
+    /// If several children have the same name, the first one is used.
+    pub fn find(&self, entity: Entity, path: &str) -> Option<Entity> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(entity, |entity, name| {
+                self.children(entity).iter().cloned().find(|child| {
+                    self.names
+                        .get(*child)
+                        .map_or(false, |named| named.name == name)
+                })
+            })
+    }
+
+    /// Deletes `entity` and all of its descendants.
+    pub fn delete_recursive(&self, entity: Entity) -> Result<(), Error> {
+        for descendant in self.descendants(entity) {
+            // Descendants may have been deleted already, which is fine.
+            let _ = self.entities.delete(descendant);
+        }
+        self.entities
+            .delete(entity)
+            .map_err(|_| format_err!("Entity {:?} was already deleted", entity))
+    }
+
+    /// Creates a copy of `entity` and its descendants with `LazyUpdate`, and returns the copy
+    /// of `entity`.
+    ///
+    /// The copy is made a child of `parent`, or has no parent if `None`. The `Parent`
+    /// and `Named` components are copied, any other components have to be added by
+    /// `clone_components`, which is called with each original entity and the builder of its
+    /// copy. The components are inserted when the world is maintained.
+    ///
+    /// ```rust,ignore
+    /// let copy = hierarchy.clone_recursive(ship, None, &lazy, |original, builder| {
+    ///     match transforms.get(original) {
+    ///         Some(transform) => builder.with(transform.clone()),
+    ///         None => builder,
+    ///     }
+    /// });
+    /// ```
+    pub fn clone_recursive<F>(
+        &self,
+        entity: Entity,
+        parent: Option<Entity>,
+        lazy: &LazyUpdate,
+        mut clone_components: F,
+    ) -> Entity
+    where
+        F: for<'b> FnMut(Entity, LazyBuilder<'b>) -> LazyBuilder<'b>,
+    {
+        let copy = self.clone_entity(entity, parent, lazy, &mut clone_components);
+        let mut queue = VecDeque::new();
+        queue.push_back((entity, copy));
+        while let Some((original, copy)) = queue.pop_front() {
+            for child in self.children(original) {
+                let child_copy = self.clone_entity(*child, Some(copy), lazy, &mut clone_components);
+                queue.push_back((*child, child_copy));
+            }
+        }
+        copy
+    }
+
+    fn clone_entity<F>(
+        &self,
+        entity: Entity,
+        parent: Option<Entity>,
+        lazy: &LazyUpdate,
+        clone_components: &mut F,
+    ) -> Entity
+    where
+        F: for<'b> FnMut(Entity, LazyBuilder<'b>) -> LazyBuilder<'b>,
+    {
+        let mut builder = lazy.create_entity(&self.entities);
+        if let Some(parent) = parent {
+            builder = builder.with(Parent::new(parent));
+        }
+        if let Some(named) = self.names.get(entity) {
+            builder = builder.with(named.clone());
+        }
+        clone_components(entity, builder).build()
+    }
+}
+
+/// Depth-first iterator over the descendants of an entity, see `HierarchyQuery::descendants`.
+#[allow(missing_debug_implementations)]
+pub struct DepthFirst<'a> {
+    hierarchy: &'a ParentHierarchy,
+    stack: Vec<Entity>,
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        let entity = self.stack.pop()?;
+        self.stack
+            .extend(self.hierarchy.children(entity).iter().rev().cloned());
+        Some(entity)
+    }
+}
+
+/// Breadth-first iterator over the descendants of an entity, see
+/// `HierarchyQuery::descendants_breadth_first`.
+#[allow(missing_debug_implementations)]
+pub struct BreadthFirst<'a> {
+    hierarchy: &'a ParentHierarchy,
+    queue: VecDeque<Entity>,
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        let entity = self.queue.pop_front()?;
+        self.queue
+            .extend(self.hierarchy.children(entity).iter().cloned());
+        Some(entity)
+    }
+}
+
+/// Iterator over the ancestors of an entity, see `HierarchyQuery::ancestors`.
+#[allow(missing_debug_implementations)]
+pub struct Ancestors<'a> {
+    hierarchy: &'a ParentHierarchy,
+    entity: Entity,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        let parent = self.hierarchy.parent(self.entity)?;
+        self.entity = parent;
+        Some(parent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use specs_hierarchy::HierarchySystem;
+
+    use crate::{
+        ecs::{prelude::WorldExt, shred::RunNow},
+        WithNamed,
+    };
+
+    use super::*;
+
+    fn child(world: &mut World, parent: Entity, name: &'static str) -> Entity {
+        world
+            .create_entity()
+            .with(Parent::new(parent))
+            .named(name)
+            .build()
+    }
+
+    #[test]
+    fn queries_follow_the_hierarchy() {
+        let mut world = World::new();
+        let mut hs = HierarchySystem::<Parent>::new(&mut world);
+        hs.setup(&mut world);
+        world.register::<Named>();
+
+        let player = world.create_entity().named("player").build();
+        let arm = child(&mut world, player, "arm");
+        let head = child(&mut world, player, "head");
+        let hand = child(&mut world, arm, "hand");
+        let weapon = child(&mut world, hand, "weapon");
+        hs.run_now(&world);
+
+        {
+            let hierarchy = world.system_data::<HierarchyQuery<'_>>();
+            assert_eq!(Some(weapon), hierarchy.find(player, "arm/hand/weapon"));
+            assert_eq!(None, hierarchy.find(player, "head/weapon"));
+            assert_eq!(
+                vec![arm, hand, weapon, head],
+                hierarchy.descendants(player).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![arm, head, hand, weapon],
+                hierarchy
+                    .descendants_breadth_first(player)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![hand, arm, player],
+                hierarchy.ancestors(weapon).collect::<Vec<_>>()
+            );
+            assert_eq!(player, hierarchy.root(weapon));
+
+            let lazy = world.fetch::<LazyUpdate>();
+            let copy = hierarchy.clone_recursive(arm, Some(head), &lazy, |_, builder| builder);
+            assert_ne!(arm, copy);
+        }
+        world.maintain();
+        hs.run_now(&world);
+
+        {
+            let hierarchy = world.system_data::<HierarchyQuery<'_>>();
+            let copy = hierarchy.find(player, "head/arm/hand/weapon").unwrap();
+            assert_ne!(weapon, copy);
+
+            hierarchy.delete_recursive(arm).unwrap();
+        }
+        world.maintain();
+        assert!(!world.is_alive(weapon));
+        assert!(world.is_alive(head));
+    }
+}
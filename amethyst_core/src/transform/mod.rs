@@ -1,7 +1,15 @@
 //! `amethyst` transform ecs module
 
-pub use self::{bundle::TransformBundle, components::*, systems::*};
+pub use self::{
+    bundle::TransformBundle,
+    components::*,
+    global::GlobalTransforms,
+    hierarchy::{Ancestors, BreadthFirst, DepthFirst, HierarchyQuery},
+    systems::*,
+};
 
 pub mod bundle;
 pub mod components;
+pub mod global;
+pub mod hierarchy;
 pub mod systems;
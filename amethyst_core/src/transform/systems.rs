@@ -4,14 +4,14 @@ use crate::{
     ecs::{
         hibitset::BitSet,
         prelude::{
-            ComponentEvent, Entities, Join, ReadExpect, ReadStorage, ReaderId, System, SystemData,
-            World, WriteStorage,
+            ComponentEvent, Entities, Join, Read, ReadExpect, ReadStorage, ReaderId, System,
+            SystemData, World, WriteStorage,
         },
     },
-    SystemDesc,
+    SystemDesc, Time,
 };
 
-use crate::transform::{HierarchyEvent, Parent, ParentHierarchy, Transform};
+use crate::transform::{HierarchyEvent, InterpolatedTransform, Parent, ParentHierarchy, Transform};
 
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
@@ -140,6 +140,75 @@ impl<'a> System<'a> for TransformSystem {
     }
 }
 
+/// Updates `InterpolatedTransform` components from the `Transform` of their entity, so
+/// movement done in `fixed_update` is rendered smoothly at any frame rate.
+///
+/// Runs after the `TransformSystem`. Children are interpolated relative to the interpolated
+/// global matrix of their parent if it has an `InterpolatedTransform` itself, and relative to
+/// its `Transform::global_matrix` otherwise. Descendants of an entity with an
+/// `InterpolatedTransform` get one as well, so they move along with it.
+#[derive(Debug, Default)]
+pub struct TransformInterpolationSystem;
+
+impl<'a> System<'a> for TransformInterpolationSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, ParentHierarchy>,
+        Read<'a, Time>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, Parent>,
+        WriteStorage<'a, InterpolatedTransform>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, hierarchy, time, locals, parents, mut interpolated): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("transform_interpolation_system");
+
+        let alpha = time.interpolation_alpha();
+        let fixed_frame_number = time.fixed_frame_number();
+
+        for (interpolation, local) in (&mut interpolated, &locals).join() {
+            interpolation.update(local, fixed_frame_number);
+        }
+
+        // Compute transforms without parents.
+        for (_, interpolation, _) in (&*entities, &mut interpolated, !&parents).join() {
+            interpolation.global_matrix = interpolation.local_matrix(alpha);
+        }
+
+        // Compute transforms with parents, which are ordered after their parents.
+        for entity in hierarchy.all() {
+            let parent = match parents.get(*entity) {
+                Some(parent) => parent.entity,
+                None => continue,
+            };
+            let parent_global = match interpolated.get(parent) {
+                Some(parent_interpolation) => Some(parent_interpolation.global_matrix),
+                None => locals.get(parent).map(|local| local.global_matrix),
+            };
+            if interpolated.contains(parent) && !interpolated.contains(*entity) {
+                if let Some(local) = locals.get(*entity) {
+                    let mut interpolation = InterpolatedTransform::new();
+                    interpolation.update(local, fixed_frame_number);
+                    interpolated
+                        .insert(*entity, interpolation)
+                        .expect("Unreachable: Entities of the hierarchy are alive");
+                }
+            }
+            if let Some(interpolation) = interpolated.get_mut(*entity) {
+                let local_matrix = interpolation.local_matrix(alpha);
+                interpolation.global_matrix = match parent_global {
+                    Some(parent_global) => parent_global * local_matrix,
+                    None => local_matrix,
+                };
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -148,9 +217,13 @@ mod tests {
             shred::RunNow,
         },
         math::{Matrix4, Quaternion, Unit, Vector3},
-        transform::{Parent, Transform, TransformSystem, TransformSystemDesc},
-        SystemDesc,
+        transform::{
+            InterpolatedTransform, Parent, Transform, TransformInterpolationSystem,
+            TransformSystem, TransformSystemDesc,
+        },
+        SystemDesc, Time,
     };
+    use approx::assert_ulps_eq;
     use specs_hierarchy::{Hierarchy, HierarchySystem};
 
     // If this works, then all other tests should work.
@@ -486,4 +559,158 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn interpolates_between_fixed_updates() {
+        let (mut world, mut hs, mut system) = transform_world();
+        let mut interpolation = TransformInterpolationSystem;
+        interpolation.setup(&mut world);
+        let mut time = Time::default();
+        time.set_fixed_seconds(1.0);
+        world.insert(time);
+
+        let parent = world
+            .create_entity()
+            .with(Transform::default())
+            .with(InterpolatedTransform::new())
+            .build();
+        let mut local = Transform::default();
+        local.set_translation_x(1.0);
+        let child = world
+            .create_entity()
+            .with(local)
+            .with(Parent { entity: parent })
+            .with(InterpolatedTransform::new())
+            .build();
+
+        hs.run_now(&world);
+        system.run_now(&world);
+        interpolation.run_now(&world);
+
+        // Move the parent during a fixed update, halfway through the next one.
+        world
+            .write_storage::<Transform>()
+            .get_mut(parent)
+            .unwrap()
+            .set_translation_x(2.0);
+        {
+            let mut time = world.write_resource::<Time>();
+            time.set_delta_seconds(1.5);
+            time.start_fixed_update();
+            assert!(time.step_fixed_update());
+            assert!(!time.step_fixed_update());
+            time.finish_fixed_update();
+        }
+        hs.run_now(&world);
+        system.run_now(&world);
+        interpolation.run_now(&world);
+
+        let interpolated = world.read_storage::<InterpolatedTransform>();
+        let parent_global = interpolated.get(parent).unwrap().global_matrix();
+        let child_global = interpolated.get(child).unwrap().global_matrix();
+        assert_ulps_eq!(1.0, parent_global[(0, 3)]);
+        assert_ulps_eq!(2.0, child_global[(0, 3)]);
+        let locals = world.read_storage::<Transform>();
+        assert_ulps_eq!(2.0, locals.get(parent).unwrap().global_matrix()[(0, 3)]);
+    }
+
+    #[test]
+    fn interpolates_over_last_of_several_fixed_updates() {
+        let (mut world, mut hs, mut system) = transform_world();
+        let mut interpolation = TransformInterpolationSystem;
+        interpolation.setup(&mut world);
+        let mut time = Time::default();
+        time.set_fixed_seconds(1.0);
+        world.insert(time);
+
+        let entity = world
+            .create_entity()
+            .with(Transform::default())
+            .with(InterpolatedTransform::new())
+            .build();
+        hs.run_now(&world);
+        system.run_now(&world);
+        interpolation.run_now(&world);
+
+        // Move by 1 per fixed update during three fixed updates, halfway through the fourth.
+        world
+            .write_storage::<Transform>()
+            .get_mut(entity)
+            .unwrap()
+            .set_translation_x(3.0);
+        {
+            let mut time = world.write_resource::<Time>();
+            time.set_delta_seconds(3.5);
+            time.start_fixed_update();
+            while time.step_fixed_update() {}
+            time.finish_fixed_update();
+            assert_eq!(3, time.fixed_frame_number());
+        }
+        hs.run_now(&world);
+        system.run_now(&world);
+        interpolation.run_now(&world);
+
+        let interpolated = world.read_storage::<InterpolatedTransform>();
+        let global = interpolated.get(entity).unwrap().global_matrix();
+        assert_ulps_eq!(2.5, global[(0, 3)]);
+    }
+
+    #[test]
+    fn interpolates_descendants_of_interpolated_entities() {
+        let (mut world, mut hs, mut system) = transform_world();
+        let mut interpolation = TransformInterpolationSystem;
+        interpolation.setup(&mut world);
+        let mut time = Time::default();
+        time.set_fixed_seconds(1.0);
+        world.insert(time);
+
+        let parent = world
+            .create_entity()
+            .with(Transform::default())
+            .with(InterpolatedTransform::new())
+            .build();
+        let mut local = Transform::default();
+        local.set_translation_x(1.0);
+        let child = world
+            .create_entity()
+            .with(local)
+            .with(Parent { entity: parent })
+            .build();
+        let grandchild = world
+            .create_entity()
+            .with(Transform::default())
+            .with(Parent { entity: child })
+            .build();
+
+        hs.run_now(&world);
+        system.run_now(&world);
+        interpolation.run_now(&world);
+
+        world
+            .write_storage::<Transform>()
+            .get_mut(parent)
+            .unwrap()
+            .set_translation_x(2.0);
+        {
+            let mut time = world.write_resource::<Time>();
+            time.set_delta_seconds(1.5);
+            time.start_fixed_update();
+            while time.step_fixed_update() {}
+            time.finish_fixed_update();
+        }
+        hs.run_now(&world);
+        system.run_now(&world);
+        interpolation.run_now(&world);
+
+        let interpolated = world.read_storage::<InterpolatedTransform>();
+        let locals = world.read_storage::<Transform>();
+        for &entity in &[child, grandchild] {
+            let global = InterpolatedTransform::render_matrix(
+                interpolated.get(entity),
+                locals.get(entity).unwrap(),
+            );
+            assert_ulps_eq!(2.0, global[(0, 3)]);
+        }
+        assert_ulps_eq!(3.0, locals.get(grandchild).unwrap().global_matrix()[(0, 3)]);
+    }
 }
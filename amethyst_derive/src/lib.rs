@@ -34,6 +34,9 @@ pub fn event_reader_derive(input: TokenStream) -> TokenStream {
 /// `amethyst:assets::{PrefabData, ProgressCounter}` and
 /// `amethyst::error::Error` are imported and visible in the current scope. This
 /// is due to how Rust macros work.
+///
+/// Adding `#[prefab(Extract)]` to the type also derives `PrefabExtract`, which
+/// additionally requires `amethyst::assets::PrefabExtract` to be imported.
 #[proc_macro_derive(PrefabData, attributes(prefab))]
 pub fn prefab_data_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
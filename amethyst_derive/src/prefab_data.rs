@@ -20,6 +20,19 @@ fn impl_prefab_data_component(ast: &DeriveInput) -> TokenStream {
     let (_, ty_generics, where_clause) = ast.generics.split_for_impl();
     let lf_tokens = gen_def_lt_tokens(&ast.generics);
     let ty_tokens = gen_def_ty_params(&ast.generics);
+    let extract = if is_extract_prefab(&ast.attrs[..]) {
+        Some(quote! {
+            impl<'pfd, #lf_tokens #ty_tokens> PrefabExtract<'pfd> for #base #ty_generics #where_clause {
+                fn extract(entity: Entity,
+                           system_data: &mut Self::SystemData,
+                           _: &[Entity]) -> ::std::result::Result<Option<Self>, Error> {
+                    Ok(system_data.get(entity).cloned())
+                }
+            }
+        })
+    } else {
+        None
+    };
 
     quote! {
         impl<'pfd, #lf_tokens #ty_tokens> PrefabData<'pfd> for #base #ty_generics #where_clause {
@@ -35,6 +48,8 @@ fn impl_prefab_data_component(ast: &DeriveInput) -> TokenStream {
                 Ok(())
             }
         }
+
+        #extract
     }
 }
 
@@ -260,6 +275,50 @@ fn impl_prefab_data_aggregate(ast: &DeriveInput) -> TokenStream {
     let (_, ty_generics, where_clause) = ast.generics.split_for_impl();
     let lf_tokens = gen_def_lt_tokens(&ast.generics);
     let ty_tokens = gen_def_ty_params(&ast.generics);
+    let extract = if is_extract_prefab(&ast.attrs[..]) {
+        let extract = match &ast.data {
+            Data::Struct(ref s) => {
+                let construct = construct_extracted(quote!(#base), &s.fields, &data_types);
+                quote! {
+                    Ok(Some(#construct))
+                }
+            }
+            Data::Enum(ref e) => {
+                // the first variant which can be extracted completely is used
+                let variants = e.variants.iter().map(|variant| {
+                    let ident = &variant.ident;
+                    let construct =
+                        construct_extracted(quote!(#base::#ident), &variant.fields, &data_types);
+                    quote! {
+                        let variant = |system_data: &mut Self::SystemData|
+                            -> ::std::result::Result<Option<Self>, Error> {
+                            Ok(Some(#construct))
+                        };
+                        if let Some(data) = variant(system_data)? {
+                            return Ok(Some(data));
+                        }
+                    }
+                });
+                quote! {
+                    #(#variants)*
+                    Ok(None)
+                }
+            }
+            _ => unreachable!(),
+        };
+        Some(quote! {
+            impl<'pfd, #lf_tokens #ty_tokens> PrefabExtract<'pfd> for #base #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn extract(entity: Entity,
+                           system_data: &mut Self::SystemData,
+                           entities: &[Entity]) -> ::std::result::Result<Option<Self>, Error> {
+                    #extract
+                }
+            }
+        })
+    } else {
+        None
+    };
 
     quote! {
         impl<'pfd, #lf_tokens #ty_tokens> PrefabData<'pfd> for #base #ty_generics #where_clause {
@@ -285,6 +344,50 @@ fn impl_prefab_data_aggregate(ast: &DeriveInput) -> TokenStream {
                 Ok(ret)
             }
         }
+
+        #extract
+    }
+}
+
+/// Constructs `path` from the data extracted for each of the `fields`, returning `Ok(None)`
+/// from the surrounding function if any field can't be extracted.
+fn construct_extracted(
+    path: TokenStream,
+    fields: &Fields,
+    data_types: &[(Type, bool)],
+) -> TokenStream {
+    let values = fields.iter().map(|field| {
+        let is_component = is_component_prefab(&field.attrs[..]);
+        let ty = &field.ty;
+        let i = data_types
+            .iter()
+            .position(|t| t.0 == field.ty && t.1 == is_component)
+            .expect("Unreachable: data types are collected for all fields");
+        let tuple_index = Literal::usize_unsuffixed(i);
+        let value = if is_component {
+            quote! {
+                system_data.#tuple_index.get(entity).cloned()
+            }
+        } else {
+            quote! {
+                <#ty as PrefabExtract<'pfd>>::extract(entity, &mut system_data.#tuple_index, entities)?
+            }
+        };
+        let value = quote! {
+            match #value {
+                Some(value) => value,
+                None => return Ok(None),
+            }
+        };
+        match &field.ident {
+            Some(name) => quote! { #name: #value },
+            None => value,
+        }
+    });
+    match fields {
+        Fields::Named(_) => quote! { #path { #(#values,)* } },
+        Fields::Unnamed(_) => quote! { #path ( #(#values,)* ) },
+        Fields::Unit => quote! { #path },
     }
 }
 
@@ -323,6 +426,14 @@ fn gen_def_ty_params(generics: &Generics) -> TokenStream {
 }
 
 fn is_component_prefab(attrs: &[Attribute]) -> bool {
+    has_prefab_word(attrs, "Component")
+}
+
+fn is_extract_prefab(attrs: &[Attribute]) -> bool {
+    has_prefab_word(attrs, "Extract")
+}
+
+fn has_prefab_word(attrs: &[Attribute], word: &str) -> bool {
     for meta in attrs
         .iter()
         .filter(|attr| attr.path.segments[0].ident == "prefab")
@@ -335,7 +446,7 @@ fn is_component_prefab(attrs: &[Attribute]) -> bool {
             for nested_meta in l.nested.iter() {
                 match nested_meta {
                     NestedMeta::Meta(Meta::Path(path)) => {
-                        if let Some(true) = path.get_ident().map(|word| word == "Component") {
+                        if let Some(true) = path.get_ident().map(|ident| ident == word) {
                             return true;
                         }
                     }
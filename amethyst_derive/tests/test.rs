@@ -5,7 +5,7 @@
 )]
 use amethyst_derive::{EventReader, PrefabData};
 
-use amethyst_assets::{PrefabData, ProgressCounter};
+use amethyst_assets::{PrefabData, PrefabExtract, ProgressCounter};
 use amethyst_core::{
     ecs::{Component, DenseVecStorage, Entity, Read, SystemData, World, WriteStorage},
     shrev::{EventChannel, ReaderId},
@@ -43,7 +43,7 @@ where
 }
 
 #[derive(Clone, PrefabData, Default)]
-#[prefab(Component)]
+#[prefab(Component, Extract)]
 pub struct Stuff<T>
 where
     T: Default + Clone + Send + Sync + 'static,
@@ -85,6 +85,15 @@ pub struct Outer {
 pub struct OuterTuple(#[prefab(Component)] External);
 
 #[derive(PrefabData, Clone)]
+#[prefab(Extract)]
+pub struct Extractable {
+    stuff: Option<Stuff<usize>>,
+    #[prefab(Component)]
+    external: External,
+}
+
+#[derive(PrefabData, Clone)]
+#[prefab(Extract)]
 pub enum EnumPrefab {
     One {
         number: Stuff<usize>,
@@ -102,7 +111,10 @@ pub enum EnumPrefab {
 mod tests {
     use super::*;
     use amethyst_assets::{AssetStorage, Loader, Prefab, PrefabLoaderSystemDesc};
-    use amethyst_core::ecs::{world::EntitiesRes, Builder, Join, WorldExt};
+    use amethyst_core::{
+        ecs::{world::EntitiesRes, Builder, Join, WorldExt},
+        Parent,
+    };
     use amethyst_test::prelude::*;
 
     macro_rules! assert_prefab {
@@ -219,4 +231,57 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn extract_struct_prefabs() {
+        let mut world = World::new();
+        world.register::<Stuff<usize>>();
+        world.register::<External>();
+        world.register::<Parent>();
+        let root = world
+            .create_entity()
+            .with(External { inner: 1 })
+            .with(Stuff { inner: 10 })
+            .build();
+        let child = world
+            .create_entity()
+            .with(External { inner: 2 })
+            .with(Parent { entity: root })
+            .build();
+        world.create_entity().with(Parent { entity: child }).build();
+        world.create_entity().with(External { inner: 3 }).build();
+
+        let prefab = Prefab::<Extractable>::extract(&world, root).unwrap();
+        let entities = prefab.entities().collect::<Vec<_>>();
+        assert_eq!(entities.len(), 3);
+
+        let main = entities[0].data().unwrap();
+        assert_eq!(main.external.inner, 1);
+        assert_eq!(main.stuff.as_ref().map(|stuff| stuff.inner), Some(10));
+
+        assert_eq!(entities[1].parent(), Some(0));
+        let child = entities[1].data().unwrap();
+        assert_eq!(child.external.inner, 2);
+        assert!(child.stuff.is_none());
+
+        // entities without the required components have no data
+        assert_eq!(entities[2].parent(), Some(1));
+        assert!(entities[2].data().is_none());
+    }
+
+    #[test]
+    fn extract_first_complete_variant() {
+        let mut world = World::new();
+        world.register::<Stuff<usize>>();
+        world.register::<Stuff<String>>();
+        world.register::<External>();
+        world.register::<Parent>();
+        let entity = world.create_entity().with(External { inner: 5 }).build();
+
+        let prefab = Prefab::<EnumPrefab>::extract(&world, entity).unwrap();
+        match prefab.entities().next().unwrap().data() {
+            Some(EnumPrefab::Two { component }) => assert_eq!(component.inner, 5),
+            _ => panic!("Expected `EnumPrefab::Two` to be extracted"),
+        }
+    }
 }
@@ -14,7 +14,7 @@ use crate::{
 use amethyst_assets::{AssetStorage, Handle};
 use amethyst_core::{
     ecs::{Join, Read, ReadExpect, ReadStorage, SystemData, World},
-    transform::Transform,
+    transform::{InterpolatedTransform, Transform},
     Hidden, HiddenPropagate,
 };
 use derivative::Derivative;
@@ -202,6 +202,7 @@ impl<B: Backend, T: Base3DPassDef> RenderGroup<B, World> for DrawBase3D<B, T> {
             meshes,
             materials,
             transforms,
+            interpolated,
             joints,
             tints,
         ) = <(
@@ -213,6 +214,7 @@ impl<B: Backend, T: Base3DPassDef> RenderGroup<B, World> for DrawBase3D<B, T> {
             ReadStorage<'_, Handle<Mesh>>,
             ReadStorage<'_, Handle<Material>>,
             ReadStorage<'_, Transform>,
+            ReadStorage<'_, InterpolatedTransform>,
             ReadStorage<'_, JointTransforms>,
             ReadStorage<'_, Tint>,
         )>::fetch(resources);
@@ -229,14 +231,32 @@ impl<B: Backend, T: Base3DPassDef> RenderGroup<B, World> for DrawBase3D<B, T> {
         let statics_ref = &mut self.static_batches;
         let skinned_ref = &mut self.skinned_batches;
 
-        let static_input = || ((&materials, &meshes, &transforms, tints.maybe()), !&joints);
-        let skinned_input = || (&materials, &meshes, &transforms, tints.maybe(), &joints);
+        let transform_input = || (&transforms, interpolated.maybe());
+        let static_input = || {
+            (
+                (&materials, &meshes, transform_input(), tints.maybe()),
+                !&joints,
+            )
+        };
+        let skinned_input = || {
+            (
+                &materials,
+                &meshes,
+                transform_input(),
+                tints.maybe(),
+                &joints,
+            )
+        };
         {
             profile_scope_impl!("prepare");
             (static_input(), &visibility.visible_unordered)
                 .join()
-                .map(|(((mat, mesh, tform, tint), _), _)| {
-                    ((mat, mesh.id()), VertexArgs::from_object_data(tform, tint))
+                .map(|(((mat, mesh, (tform, interp), tint), _), _)| {
+                    let global_matrix = InterpolatedTransform::render_matrix(interp, tform);
+                    (
+                        (mat, mesh.id()),
+                        VertexArgs::from_global_matrix(global_matrix, tint),
+                    )
                 })
                 .for_each_group(|(mat, mesh_id), data| {
                     if mesh_storage.contains_id(mesh_id) {
@@ -251,11 +271,11 @@ impl<B: Backend, T: Base3DPassDef> RenderGroup<B, World> for DrawBase3D<B, T> {
 
             (skinned_input(), &visibility.visible_unordered)
                 .join()
-                .map(|((mat, mesh, tform, tint, joints), _)| {
+                .map(|((mat, mesh, (tform, interp), tint, joints), _)| {
                     (
                         (mat, mesh.id()),
-                        SkinnedVertexArgs::from_object_data(
-                            tform,
+                        SkinnedVertexArgs::from_global_matrix(
+                            InterpolatedTransform::render_matrix(interp, tform),
                             tint,
                             skinning_ref.insert(joints),
                         ),
@@ -515,13 +535,14 @@ impl<B: Backend, T: Base3DPassDef> RenderGroup<B, World> for DrawBase3DTranspare
     ) -> PrepareResult {
         profile_scope_impl!("prepare transparent");
 
-        let (mesh_storage, visibility, meshes, materials, transforms, joints, tints) =
+        let (mesh_storage, visibility, meshes, materials, transforms, interpolated, joints, tints) =
             <(
                 Read<'_, AssetStorage<Mesh>>,
                 ReadExpect<'_, Visibility>,
                 ReadStorage<'_, Handle<Mesh>>,
                 ReadStorage<'_, Handle<Material>>,
                 ReadStorage<'_, Transform>,
+                ReadStorage<'_, InterpolatedTransform>,
                 ReadStorage<'_, JointTransforms>,
                 ReadStorage<'_, Tint>,
             )>::fetch(resources);
@@ -539,13 +560,26 @@ impl<B: Backend, T: Base3DPassDef> RenderGroup<B, World> for DrawBase3DTranspare
         let skinned_ref = &mut self.skinned_batches;
         let mut changed = false;
 
-        let mut joined = ((&materials, &meshes, &transforms, tints.maybe()), !&joints).join();
+        let mut joined = (
+            (
+                &materials,
+                &meshes,
+                (&transforms, interpolated.maybe()),
+                tints.maybe(),
+            ),
+            !&joints,
+        )
+            .join();
         visibility
             .visible_ordered
             .iter()
             .filter_map(|e| joined.get_unchecked(e.id()))
-            .map(|((mat, mesh, tform, tint), _)| {
-                ((mat, mesh.id()), VertexArgs::from_object_data(tform, tint))
+            .map(|((mat, mesh, (tform, interp), tint), _)| {
+                let global_matrix = InterpolatedTransform::render_matrix(interp, tform);
+                (
+                    (mat, mesh.id()),
+                    VertexArgs::from_global_matrix(global_matrix, tint),
+                )
             })
             .for_each_group(|(mat, mesh_id), data| {
                 if mesh_storage.contains_id(mesh_id) {
@@ -558,17 +592,24 @@ impl<B: Backend, T: Base3DPassDef> RenderGroup<B, World> for DrawBase3DTranspare
             });
 
         if self.pipeline_skinned.is_some() {
-            let mut joined = (&materials, &meshes, &transforms, tints.maybe(), &joints).join();
+            let mut joined = (
+                &materials,
+                &meshes,
+                (&transforms, interpolated.maybe()),
+                tints.maybe(),
+                &joints,
+            )
+                .join();
 
             visibility
                 .visible_ordered
                 .iter()
                 .filter_map(|e| joined.get_unchecked(e.id()))
-                .map(|(mat, mesh, tform, tint, joints)| {
+                .map(|(mat, mesh, (tform, interp), tint, joints)| {
                     (
                         (mat, mesh.id()),
-                        SkinnedVertexArgs::from_object_data(
-                            tform,
+                        SkinnedVertexArgs::from_global_matrix(
+                            InterpolatedTransform::render_matrix(interp, tform),
                             tint,
                             skinning_ref.insert(joints),
                         ),
@@ -12,7 +12,7 @@ use crate::{
 use amethyst_assets::AssetStorage;
 use amethyst_core::{
     ecs::{Join, Read, ReadExpect, ReadStorage, SystemData, World},
-    transform::Transform,
+    transform::{InterpolatedTransform, Transform},
     Hidden, HiddenPropagate,
 };
 use derivative::Derivative;
@@ -114,6 +114,7 @@ impl<B: Backend> RenderGroup<B, World> for DrawFlat2D<B> {
             hidden_props,
             sprite_renders,
             transforms,
+            interpolated,
             tints,
         ) = <(
             Read<'_, AssetStorage<SpriteSheet>>,
@@ -123,6 +124,7 @@ impl<B: Backend> RenderGroup<B, World> for DrawFlat2D<B> {
             ReadStorage<'_, HiddenPropagate>,
             ReadStorage<'_, SpriteRender>,
             ReadStorage<'_, Transform>,
+            ReadStorage<'_, InterpolatedTransform>,
             ReadStorage<'_, Tint>,
         )>::fetch(world);
 
@@ -140,16 +142,17 @@ impl<B: Backend> RenderGroup<B, World> for DrawFlat2D<B> {
             (
                 &sprite_renders,
                 &transforms,
+                interpolated.maybe(),
                 tints.maybe(),
                 &visibility.visible_unordered,
             )
                 .join()
-                .filter_map(|(sprite_render, global, tint, _)| {
-                    let (batch_data, texture) = SpriteArgs::from_data(
+                .filter_map(|(sprite_render, global, interp, tint, _)| {
+                    let (batch_data, texture) = SpriteArgs::from_global_matrix(
                         &tex_storage,
                         &sprite_sheet_storage,
                         &sprite_render,
-                        &global,
+                        InterpolatedTransform::render_matrix(interp, global),
                         tint,
                     )?;
                     let (tex_id, _) = textures_ref.insert(
@@ -293,15 +296,23 @@ impl<B: Backend> RenderGroup<B, World> for DrawFlat2DTransparent<B> {
         #[cfg(feature = "profiler")]
         profile_scope!("prepare transparent");
 
-        let (sprite_sheet_storage, tex_storage, visibility, sprite_renders, transforms, tints) =
-            <(
-                Read<'_, AssetStorage<SpriteSheet>>,
-                Read<'_, AssetStorage<Texture>>,
-                ReadExpect<'_, SpriteVisibility>,
-                ReadStorage<'_, SpriteRender>,
-                ReadStorage<'_, Transform>,
-                ReadStorage<'_, Tint>,
-            )>::fetch(world);
+        let (
+            sprite_sheet_storage,
+            tex_storage,
+            visibility,
+            sprite_renders,
+            transforms,
+            interpolated,
+            tints,
+        ) = <(
+            Read<'_, AssetStorage<SpriteSheet>>,
+            Read<'_, AssetStorage<Texture>>,
+            ReadExpect<'_, SpriteVisibility>,
+            ReadStorage<'_, SpriteRender>,
+            ReadStorage<'_, Transform>,
+            ReadStorage<'_, InterpolatedTransform>,
+            ReadStorage<'_, Tint>,
+        )>::fetch(world);
 
         self.env.process(factory, index, world);
         self.sprites.swap_clear();
@@ -314,17 +325,23 @@ impl<B: Backend> RenderGroup<B, World> for DrawFlat2DTransparent<B> {
             #[cfg(feature = "profiler")]
             profile_scope!("gather_sprites_trans");
 
-            let mut joined = (&sprite_renders, &transforms, tints.maybe()).join();
+            let mut joined = (
+                &sprite_renders,
+                &transforms,
+                interpolated.maybe(),
+                tints.maybe(),
+            )
+                .join();
             visibility
                 .visible_ordered
                 .iter()
                 .filter_map(|e| joined.get_unchecked(e.id()))
-                .filter_map(|(sprite_render, global, tint)| {
-                    let (batch_data, texture) = SpriteArgs::from_data(
+                .filter_map(|(sprite_render, global, interp, tint)| {
+                    let (batch_data, texture) = SpriteArgs::from_global_matrix(
                         &tex_storage,
                         &sprite_sheet_storage,
                         &sprite_render,
-                        &global,
+                        InterpolatedTransform::render_matrix(interp, global),
                         tint,
                     )?;
                     let (tex_id, this_changed) = textures_ref.insert(
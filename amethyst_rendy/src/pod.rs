@@ -96,7 +96,14 @@ impl VertexArgs {
     /// and `TintComponent` components.
     #[inline]
     pub fn from_object_data(transform: &Transform, tint: Option<&TintComponent>) -> Self {
-        let model: [[f32; 4]; 4] = convert::<_, Matrix4<f32>>(*transform.global_matrix()).into();
+        Self::from_global_matrix(transform.global_matrix(), tint)
+    }
+
+    /// Populates a `VertexArgs` instance-rate structure from a global transformation matrix,
+    /// e.g. the one returned by `InterpolatedTransform::render_matrix`.
+    #[inline]
+    pub fn from_global_matrix(global_matrix: &Matrix4<f32>, tint: Option<&TintComponent>) -> Self {
+        let model: [[f32; 4]; 4] = convert::<_, Matrix4<f32>>(*global_matrix).into();
         VertexArgs {
             model: model.into(),
             tint: tint.map_or([1.0; 4].into(), |t| t.0.into_pod()),
@@ -157,7 +164,17 @@ impl SkinnedVertexArgs {
         tint: Option<&TintComponent>,
         joints_offset: u32,
     ) -> Self {
-        let model: [[f32; 4]; 4] = convert::<_, Matrix4<f32>>(*transform.global_matrix()).into();
+        Self::from_global_matrix(transform.global_matrix(), tint, joints_offset)
+    }
+
+    /// Populate `SkinnedVertexArgs` from a global transformation matrix and `TintComponent`
+    #[inline]
+    pub fn from_global_matrix(
+        global_matrix: &Matrix4<f32>,
+        tint: Option<&TintComponent>,
+        joints_offset: u32,
+    ) -> Self {
+        let model: [[f32; 4]; 4] = convert::<_, Matrix4<f32>>(*global_matrix).into();
         SkinnedVertexArgs {
             model: model.into(),
             tint: tint.map_or([1.0; 4].into(), |t| t.0.into_pod()),
@@ -339,6 +356,24 @@ impl SpriteArgs {
         sprite_render: &SpriteRender,
         transform: &Transform,
         tint: Option<&TintComponent>,
+    ) -> Option<(Self, &'a Handle<Texture>)> {
+        Self::from_global_matrix(
+            tex_storage,
+            sprite_storage,
+            sprite_render,
+            transform.global_matrix(),
+            tint,
+        )
+    }
+
+    /// Extracts POD vertex data for a sprite like `from_data`, but from a global transformation
+    /// matrix, e.g. the one returned by `InterpolatedTransform::render_matrix`.
+    pub fn from_global_matrix<'a>(
+        tex_storage: &AssetStorage<Texture>,
+        sprite_storage: &'a AssetStorage<SpriteSheet>,
+        sprite_render: &SpriteRender,
+        global_matrix: &Matrix4<f32>,
+        tint: Option<&TintComponent>,
     ) -> Option<(Self, &'a Handle<Texture>)> {
         let sprite_sheet = sprite_storage.get(&sprite_render.sprite_sheet)?;
         if !tex_storage.contains(&sprite_sheet.texture) {
@@ -347,7 +382,7 @@ impl SpriteArgs {
 
         let sprite = &sprite_sheet.sprites[sprite_render.sprite_number];
 
-        let transform = convert::<_, Matrix4<f32>>(*transform.global_matrix());
+        let transform = convert::<_, Matrix4<f32>>(*global_matrix);
         let dir_x = transform.column(0) * sprite.width;
         let dir_y = transform.column(1) * -sprite.height;
         let pos = transform * Vector4::new(-sprite.offsets[0], -sprite.offsets[1], 0.0, 1.0);
@@ -8,8 +8,8 @@ use amethyst_core::{
         hibitset::BitSet,
         prelude::{Entities, Entity, Join, Read, ReadStorage, System, Write},
     },
-    math::{Point3, Vector3},
-    Hidden, HiddenPropagate, Transform,
+    math::{Matrix4, Point3, Vector3},
+    Hidden, HiddenPropagate, InterpolatedTransform, Transform,
 };
 use derivative::Derivative;
 use std::cmp::Ordering;
@@ -68,11 +68,22 @@ impl<'a> System<'a> for SpriteVisibilitySortingSystem {
         ReadStorage<'a, Camera>,
         ReadStorage<'a, Transparent>,
         ReadStorage<'a, Transform>,
+        ReadStorage<'a, InterpolatedTransform>,
     );
 
     fn run(
         &mut self,
-        (entities, mut visibility, hidden, hidden_prop, active, camera, transparent, transform): Self::SystemData,
+        (
+            entities,
+            mut visibility,
+            hidden,
+            hidden_prop,
+            active,
+            camera,
+            transparent,
+            transform,
+            interpolated,
+        ): Self::SystemData,
     ) {
         #[cfg(feature = "profiler")]
         profile_scope!("sprite_visibility_sorting_system");
@@ -81,22 +92,38 @@ impl<'a> System<'a> for SpriteVisibilitySortingSystem {
 
         // The camera position is used to determine culling, but the sprites are ordered based on
         // the Z coordinate
-        let camera: Option<&Transform> = active
+        let camera: Option<&Matrix4<f32>> = active
             .entity
-            .and_then(|a| transform.get(a))
-            .or_else(|| (&camera, &transform).join().map(|ct| ct.1).next());
-        let camera_backward = camera
-            .map(|c| c.global_matrix().column(2).xyz())
-            .unwrap_or_else(Vector3::z);
+            .and_then(|a| {
+                transform
+                    .get(a)
+                    .map(|t| InterpolatedTransform::render_matrix(interpolated.get(a), t))
+            })
+            .or_else(|| {
+                (&camera, &transform, interpolated.maybe())
+                    .join()
+                    .map(|(_, t, i)| InterpolatedTransform::render_matrix(i, t))
+                    .next()
+            });
+        let camera_backward = camera.map(|c| c.column(2).xyz()).unwrap_or_else(Vector3::z);
         let camera_centroid = camera
-            .map(|t| t.global_matrix().transform_point(&origin))
+            .map(|m| m.transform_point(&origin))
             .unwrap_or_else(|| origin);
 
         self.centroids.clear();
         self.centroids.extend(
-            (&*entities, &transform, !&hidden, !&hidden_prop)
+            (
+                &*entities,
+                &transform,
+                interpolated.maybe(),
+                !&hidden,
+                !&hidden_prop,
+            )
                 .join()
-                .map(|(e, t, _, _)| (e, t.global_matrix().transform_point(&origin)))
+                .map(|(e, t, i, _, _)| {
+                    let matrix = InterpolatedTransform::render_matrix(i, t);
+                    (e, matrix.transform_point(&origin))
+                })
                 // filter entities behind the camera
                 .filter(|(_, c)| (c - camera_centroid).dot(&camera_backward) < 0.0)
                 .map(|(entity, centroid)| Internals {
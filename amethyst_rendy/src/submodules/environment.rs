@@ -17,7 +17,7 @@ use crate::{
 use amethyst_core::{
     ecs::{Join, ReadStorage, SystemData, World},
     math::{convert, Vector3},
-    transform::Transform,
+    transform::{InterpolatedTransform, Transform},
 };
 use glsl_layout::*;
 
@@ -186,16 +186,21 @@ impl<B: Backend> PerImageEnvironmentSub<B> {
             }
             .std140();
 
-            let (lights, transforms) =
-                <(ReadStorage<'_, Light>, ReadStorage<'_, Transform>)>::fetch(world);
+            let (lights, transforms, interpolated) = <(
+                ReadStorage<'_, Light>,
+                ReadStorage<'_, Transform>,
+                ReadStorage<'_, InterpolatedTransform>,
+            )>::fetch(world);
 
-            let point_lights = (&lights, &transforms)
+            let point_lights = (&lights, &transforms, interpolated.maybe())
                 .join()
-                .filter_map(|(light, transform)| match light {
+                .filter_map(|(light, transform, interpolated)| match light {
                     Light::Point(light) => Some(
                         pod::PointLight {
                             position: convert::<_, Vector3<f32>>(
-                                transform.global_matrix().column(3).xyz(),
+                                InterpolatedTransform::render_matrix(interpolated, transform)
+                                    .column(3)
+                                    .xyz(),
                             )
                             .into_pod(),
                             color: light.color.into_pod(),
@@ -222,14 +227,16 @@ impl<B: Backend> PerImageEnvironmentSub<B> {
                 })
                 .take(MAX_DIR_LIGHTS);
 
-            let spot_lights = (&lights, &transforms)
+            let spot_lights = (&lights, &transforms, interpolated.maybe())
                 .join()
-                .filter_map(|(light, transform)| {
+                .filter_map(|(light, transform, interpolated)| {
                     if let Light::Spot(ref light) = *light {
                         Some(
                             pod::SpotLight {
                                 position: convert::<_, Vector3<f32>>(
-                                    transform.global_matrix().column(3).xyz(),
+                                    InterpolatedTransform::render_matrix(interpolated, transform)
+                                        .column(3)
+                                        .xyz(),
                                 )
                                 .into_pod(),
                                 color: light.color.into_pod(),
@@ -7,7 +7,7 @@ use crate::{
 use amethyst_core::{
     ecs::{Entities, Entity, Join, Read, ReadStorage, SystemData, World},
     math::{convert, Matrix4, Vector3},
-    transform::Transform,
+    transform::{InterpolatedTransform, Transform},
 };
 use glsl_layout::*;
 
@@ -64,44 +64,57 @@ impl CameraGatherer {
     /// the appropriate camera to use for projection, and returns the camera position and extracted
     /// projection matrix.
     ///
-    /// The matrix returned is the camera's `Projection` matrix and the camera `Transform::global_view_matrix`
+    /// The matrix returned is the camera's `Projection` matrix and the camera `Transform::global_view_matrix`,
+    /// or `InterpolatedTransform::global_view_matrix` if the camera has an `InterpolatedTransform`.
     pub fn gather(world: &World) -> Self {
         #[cfg(feature = "profiler")]
         profile_scope!("gather_cameras");
 
-        let (active_camera, cameras, transforms) = <(
+        let (active_camera, cameras, transforms, interpolated) = <(
             Read<'_, ActiveCamera>,
             ReadStorage<'_, Camera>,
             ReadStorage<'_, Transform>,
+            ReadStorage<'_, InterpolatedTransform>,
         )>::fetch(world);
 
         let defcam = Camera::standard_2d(1.0, 1.0);
         let identity = Transform::default();
 
-        let (camera, transform) = active_camera
+        let (camera, transform, interpolated) = active_camera
             .entity
             .as_ref()
             .and_then(|ac| {
-                cameras
-                    .get(*ac)
-                    .map(|camera| (camera, transforms.get(*ac).unwrap_or(&identity)))
+                cameras.get(*ac).map(|camera| {
+                    (
+                        camera,
+                        transforms.get(*ac).unwrap_or(&identity),
+                        interpolated.get(*ac),
+                    )
+                })
             })
             .unwrap_or_else(|| {
-                (&cameras, &transforms)
+                (&cameras, &transforms, interpolated.maybe())
                     .join()
                     .next()
-                    .unwrap_or((&defcam, &identity))
+                    .unwrap_or((&defcam, &identity, None))
             });
 
-        let camera_position =
-            convert::<_, Vector3<f32>>(transform.global_matrix().column(3).xyz()).into_pod();
+        let camera_position = convert::<_, Vector3<f32>>(
+            InterpolatedTransform::render_matrix(interpolated, transform)
+                .column(3)
+                .xyz(),
+        )
+        .into_pod();
 
         let proj = camera.as_matrix();
-        let view = transform.global_view_matrix();
+        let view = interpolated.map_or_else(
+            || transform.global_view_matrix(),
+            InterpolatedTransform::global_view_matrix,
+        );
 
         let proj_view: [[f32; 4]; 4] = ((*proj) * view).into();
         let proj: [[f32; 4]; 4] = (*proj).into();
-        let view: [[f32; 4]; 4] = convert::<_, Matrix4<f32>>(transform.global_view_matrix()).into();
+        let view: [[f32; 4]; 4] = convert::<_, Matrix4<f32>>(view).into();
 
         let projview = pod::ViewArgs {
             proj: proj.into(),
@@ -11,9 +11,11 @@ use crate::{
     types::{Backend, Mesh, Texture},
     visibility::Visibility,
 };
-use amethyst_assets::{AssetStorage, Handle, HotReloadStrategy, ProcessingState, ThreadPool};
+use amethyst_assets::{
+    AssetStats, AssetStorage, Handle, HotReloadStrategy, ProcessingState, ThreadPool,
+};
 use amethyst_core::{
-    components::Transform,
+    components::{InterpolatedTransform, Transform},
     ecs::{Read, ReadExpect, ReadStorage, RunNow, System, SystemData, World, Write, WriteExpect},
     timing::Time,
     Hidden, HiddenPropagate,
@@ -80,6 +82,7 @@ type SetupData<'a> = (
     ReadStorage<'a, DebugLinesComponent>,
     ReadStorage<'a, Transparent>,
     ReadStorage<'a, Transform>,
+    ReadStorage<'a, InterpolatedTransform>,
     ReadStorage<'a, SpriteRender>,
     Option<Read<'a, Visibility>>,
     Read<'a, ActiveCamera>,
@@ -194,11 +197,12 @@ impl<'a, B: Backend> System<'a> for MeshProcessorSystem<B> {
         ReadExpect<'a, Arc<ThreadPool>>,
         Option<Read<'a, HotReloadStrategy>>,
         ReadExpect<'a, Factory<B>>,
+        Read<'a, AssetStats>,
     );
 
     fn run(
         &mut self,
-        (mut mesh_storage, queue_id, time, pool, strategy, factory): Self::SystemData,
+        (mut mesh_storage, queue_id, time, pool, strategy, factory, stats): Self::SystemData,
     ) {
         #[cfg(feature = "profiler")]
         profile_scope!("mesh_processor");
@@ -217,6 +221,7 @@ impl<'a, B: Backend> System<'a> for MeshProcessorSystem<B> {
             &**pool,
             strategy.as_deref(),
         );
+        stats.update(&*mesh_storage);
     }
 }
 
@@ -232,11 +237,12 @@ impl<'a, B: Backend> System<'a> for TextureProcessorSystem<B> {
         ReadExpect<'a, Arc<ThreadPool>>,
         Option<Read<'a, HotReloadStrategy>>,
         WriteExpect<'a, Factory<B>>,
+        Read<'a, AssetStats>,
     );
 
     fn run(
         &mut self,
-        (mut texture_storage, queue_id, time, pool, strategy, mut factory): Self::SystemData,
+        (mut texture_storage, queue_id, time, pool, strategy, mut factory, stats): Self::SystemData,
     ) {
         #[cfg(feature = "profiler")]
         profile_scope!("texture_processor");
@@ -264,6 +270,7 @@ impl<'a, B: Backend> System<'a> for TextureProcessorSystem<B> {
             &**pool,
             strategy.as_deref(),
         );
+        stats.update(&*texture_storage);
     }
 }
 
@@ -11,7 +11,7 @@ use amethyst_core::{
         },
     },
     math::{convert, distance_squared, Matrix4, Point3, Vector4},
-    Hidden, HiddenPropagate, Transform,
+    Hidden, HiddenPropagate, InterpolatedTransform, Transform,
 };
 
 use serde::{Deserialize, Serialize};
@@ -103,6 +103,7 @@ impl<'a> System<'a> for VisibilitySortingSystem {
         ReadStorage<'a, Camera>,
         ReadStorage<'a, Transparent>,
         ReadStorage<'a, Transform>,
+        ReadStorage<'a, InterpolatedTransform>,
         ReadStorage<'a, BoundingSphere>,
     );
 
@@ -117,6 +118,7 @@ impl<'a> System<'a> for VisibilitySortingSystem {
             camera,
             transparent,
             transform,
+            interpolated,
             bound,
         ): Self::SystemData,
     ) {
@@ -127,17 +129,18 @@ impl<'a> System<'a> for VisibilitySortingSystem {
         let defcam = Camera::standard_2d(1.0, 1.0);
         let identity = Transform::default();
 
-        let mut camera_join = (&camera, &transform).join();
-        let (camera, camera_transform) = active
+        let mut camera_join = (&camera, &transform, interpolated.maybe()).join();
+        let (camera, camera_transform, camera_interpolated) = active
             .entity
             .and_then(|a| camera_join.get(a, &entities))
             .or_else(|| camera_join.next())
-            .unwrap_or((&defcam, &identity));
+            .unwrap_or((&defcam, &identity, None));
+        let camera_matrix =
+            InterpolatedTransform::render_matrix(camera_interpolated, camera_transform);
 
-        let camera_centroid = camera_transform.global_matrix().transform_point(&origin);
+        let camera_centroid = camera_matrix.transform_point(&origin);
         let frustum = Frustum::new(
-            convert::<_, Matrix4<f32>>(*camera.as_matrix())
-                * camera_transform.global_matrix().try_inverse().unwrap(),
+            convert::<_, Matrix4<f32>>(*camera.as_matrix()) * camera_matrix.try_inverse().unwrap(),
         );
 
         self.centroids.clear();
@@ -145,14 +148,15 @@ impl<'a> System<'a> for VisibilitySortingSystem {
             (
                 &*entities,
                 &transform,
+                interpolated.maybe(),
                 bound.maybe(),
                 !&hidden,
                 !&hidden_prop,
             )
                 .join()
-                .map(|(entity, transform, sphere, _, _)| {
+                .map(|(entity, transform, interpolated, sphere, _, _)| {
                     let pos = sphere.map_or(&origin, |s| &s.center);
-                    let matrix = transform.global_matrix();
+                    let matrix = InterpolatedTransform::render_matrix(interpolated, transform);
                     (
                         entity,
                         matrix.transform_point(&pos),
@@ -7,7 +7,7 @@ use amethyst_core::{
     },
     geometry::{Plane, Ray},
     math::{self, clamp, convert, Matrix4, Point2, Point3, Vector2, Vector3, Vector4},
-    transform::Transform,
+    transform::{InterpolatedTransform, Transform},
     Hidden,
 };
 
@@ -195,13 +195,14 @@ impl<B: Backend, T: Tile, E: CoordinateEncoder, Z: DrawTiles2DBounds> RenderGrou
         profile_scope!("prepare");
 
         let mut changed = false;
-        let (sprite_sheet_storage, tex_storage, hiddens, tile_maps, transforms) =
+        let (sprite_sheet_storage, tex_storage, hiddens, tile_maps, transforms, interpolated) =
             <(
                 Read<'_, AssetStorage<SpriteSheet>>,
                 Read<'_, AssetStorage<Texture>>,
                 ReadStorage<'_, Hidden>,
                 ReadStorage<'_, TileMap<T, E>>,
                 ReadStorage<'_, Transform>,
+                ReadStorage<'_, InterpolatedTransform>,
             )>::fetch(world);
 
         let sprites_ref = &mut self.sprites;
@@ -219,7 +220,14 @@ impl<B: Backend, T: Tile, E: CoordinateEncoder, Z: DrawTiles2DBounds> RenderGrou
             sprite_dimensions: Default::default(),
         };
 
-        for (tile_map, _, transform) in (&tile_maps, !&hiddens, transforms.maybe()).join() {
+        for (tile_map, _, transform, interpolated) in (
+            &tile_maps,
+            !&hiddens,
+            transforms.maybe(),
+            interpolated.maybe(),
+        )
+            .join()
+        {
             let maybe_sheet = tile_map
                 .sprite_sheet
                 .as_ref()
@@ -234,7 +242,7 @@ impl<B: Backend, T: Tile, E: CoordinateEncoder, Z: DrawTiles2DBounds> RenderGrou
             let map_coordinate_transform: [[f32; 4]; 4] = (*tile_map.transform()).into();
 
             let map_transform: [[f32; 4]; 4] = if let Some(transform) = transform {
-                (*transform.global_matrix()).into()
+                (*InterpolatedTransform::render_matrix(interpolated, transform)).into()
             } else {
                 Matrix4::identity().into()
             };
@@ -0,0 +1,414 @@
+//! Timers firing typed events after a delay or at a fixed interval.
+
+use std::{marker::PhantomData, time::Duration};
+
+use amethyst_core::{
+    ecs::{Component, DenseVecStorage, Entities, Entity, Join, Read, System, Write, WriteStorage},
+    shrev::EventChannel,
+    timing::Time,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// A timer which fires its event `E` once after a delay, or repeatedly at a fixed interval.
+///
+/// Attached to an entity as a component, or scheduled with the `TimerScheduler<E>` resource,
+/// timers are advanced by the `TimerSystem<E>`, which writes a `TimerEvent<E>` every time one
+/// fires. By default timers run on the scaled game time, so they respect
+/// `Time::time_scale`; `real_time` makes them ignore it. Making the `TimerSystem<E>`
+/// `pausable` pauses all timers firing `E` together with the rest of the game.
+///
+/// A finished one-shot timer stays on its entity, which makes it usable as a cooldown:
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use amethyst_utils::timer::Timer;
+/// let mut cooldown = Timer::once(Duration::from_millis(500), ());
+/// assert!(!cooldown.is_finished());
+/// assert_eq!(1, cooldown.tick(Duration::from_millis(600)));
+/// assert!(cooldown.is_finished());
+/// // Fire the weapon and start the cooldown again.
+/// cooldown.reset();
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timer<E> {
+    duration: Duration,
+    elapsed: Duration,
+    repeating: bool,
+    real_time: bool,
+    paused: bool,
+    finished: bool,
+    event: E,
+}
+
+impl<E> Timer<E> {
+    /// Creates a timer firing `event` once after `delay`.
+    pub fn once(delay: Duration, event: E) -> Self {
+        Timer {
+            duration: delay,
+            elapsed: Duration::from_secs(0),
+            repeating: false,
+            real_time: false,
+            paused: false,
+            finished: false,
+            event,
+        }
+    }
+
+    /// Creates a timer firing `event` every `interval`.
+    pub fn repeating(interval: Duration, event: E) -> Self {
+        Timer {
+            repeating: true,
+            ..Timer::once(interval, event)
+        }
+    }
+
+    /// Makes the timer run on real time, ignoring `Time::time_scale`.
+    pub fn real_time(mut self) -> Self {
+        self.real_time = true;
+        self
+    }
+
+    /// Returns the delay of a one-shot timer, or the interval of a repeating one.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the time elapsed since the timer was started or last fired.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the time left until the timer fires next, zero for finished timers.
+    pub fn remaining(&self) -> Duration {
+        if self.finished {
+            Duration::from_secs(0)
+        } else {
+            self.duration - self.elapsed.min(self.duration)
+        }
+    }
+
+    /// Returns the event fired by the timer.
+    pub fn event(&self) -> &E {
+        &self.event
+    }
+
+    /// Returns `true` if the timer is repeating.
+    pub fn is_repeating(&self) -> bool {
+        self.repeating
+    }
+
+    /// Returns `true` if the timer runs on real time.
+    pub fn is_real_time(&self) -> bool {
+        self.real_time
+    }
+
+    /// Returns `true` if the timer is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns `true` if the timer is a one-shot timer which has fired already.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Pauses the timer, it doesn't advance until it's resumed.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes the paused timer.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Starts the timer over, so it fires again after its full duration.
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_secs(0);
+        self.finished = false;
+    }
+
+    /// Advances the timer by `delta`, unless it's paused or finished, and returns how often it
+    /// fired.
+    ///
+    /// A repeating timer can fire several times if `delta` is longer than its interval, but
+    /// fires at most once per call if its interval is zero.
+    pub fn tick(&mut self, delta: Duration) -> u32 {
+        if self.paused || self.finished {
+            return 0;
+        }
+        self.elapsed += delta;
+        if self.elapsed < self.duration {
+            return 0;
+        }
+        if !self.repeating {
+            self.elapsed = self.duration;
+            self.finished = true;
+            return 1;
+        }
+        if self.duration == Duration::from_secs(0) {
+            self.elapsed = Duration::from_secs(0);
+            return 1;
+        }
+        let mut fired = 0;
+        while self.elapsed >= self.duration {
+            self.elapsed -= self.duration;
+            fired += 1;
+        }
+        fired
+    }
+}
+
+impl<E> Component for Timer<E>
+where
+    E: Send + Sync + 'static,
+{
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Identifies a timer scheduled with a `TimerScheduler`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TimerId(u64);
+
+/// Resource scheduling timers which don't belong to an entity, e.g. for delayed actions.
+///
+/// Finished one-shot timers are removed when they fired.
+#[derive(Debug)]
+pub struct TimerScheduler<E> {
+    timers: Vec<(TimerId, Timer<E>)>,
+    next_id: u64,
+}
+
+impl<E> Default for TimerScheduler<E> {
+    fn default() -> Self {
+        TimerScheduler {
+            timers: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<E> TimerScheduler<E> {
+    /// Schedules `timer` and returns its id.
+    pub fn schedule(&mut self, timer: Timer<E>) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push((id, timer));
+        id
+    }
+
+    /// Schedules `event` to be fired once after `delay`.
+    pub fn once(&mut self, delay: Duration, event: E) -> TimerId {
+        self.schedule(Timer::once(delay, event))
+    }
+
+    /// Schedules `event` to be fired every `interval`.
+    pub fn repeating(&mut self, interval: Duration, event: E) -> TimerId {
+        self.schedule(Timer::repeating(interval, event))
+    }
+
+    /// Returns the scheduled timer with id `id`.
+    pub fn get(&self, id: TimerId) -> Option<&Timer<E>> {
+        self.timers
+            .iter()
+            .find(|(timer_id, _)| *timer_id == id)
+            .map(|(_, timer)| timer)
+    }
+
+    /// Returns the scheduled timer with id `id` mutably, e.g. to pause it.
+    pub fn get_mut(&mut self, id: TimerId) -> Option<&mut Timer<E>> {
+        self.timers
+            .iter_mut()
+            .find(|(timer_id, _)| *timer_id == id)
+            .map(|(_, timer)| timer)
+    }
+
+    /// Removes the scheduled timer with id `id`, so it doesn't fire anymore.
+    pub fn cancel(&mut self, id: TimerId) -> Option<Timer<E>> {
+        let index = self
+            .timers
+            .iter()
+            .position(|(timer_id, _)| *timer_id == id)?;
+        Some(self.timers.remove(index).1)
+    }
+
+    /// Returns the number of scheduled timers.
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    /// Returns `true` if no timers are scheduled.
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+}
+
+/// What a fired timer belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerSource {
+    /// A `Timer` component of the entity.
+    Entity(Entity),
+    /// A timer of the `TimerScheduler`.
+    Scheduler(TimerId),
+}
+
+/// Written to the `EventChannel<TimerEvent<E>>` whenever a timer with event `E` fires.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerEvent<E> {
+    /// What the timer belongs to.
+    pub source: TimerSource,
+    /// The event of the timer.
+    pub event: E,
+}
+
+/// Advances all `Timer<E>` components and the timers of the `TimerScheduler<E>`, and writes a
+/// `TimerEvent<E>` for every time one fires.
+#[derive(Debug)]
+pub struct TimerSystem<E> {
+    _marker: PhantomData<E>,
+}
+
+impl<E> TimerSystem<E> {
+    /// Creates a new `TimerSystem`.
+    pub fn new() -> Self {
+        TimerSystem {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<E> Default for TimerSystem<E> {
+    fn default() -> Self {
+        TimerSystem::new()
+    }
+}
+
+impl<'a, E> System<'a> for TimerSystem<E>
+where
+    E: Clone + Send + Sync + 'static,
+{
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        WriteStorage<'a, Timer<E>>,
+        Write<'a, TimerScheduler<E>>,
+        Write<'a, EventChannel<TimerEvent<E>>>,
+    );
+
+    fn run(&mut self, (entities, time, mut timers, mut scheduler, mut events): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("timer_system");
+
+        let delta = |timer: &Timer<E>| {
+            if timer.real_time {
+                time.delta_real_time()
+            } else {
+                time.delta_time()
+            }
+        };
+
+        for (entity, timer) in (&entities, &mut timers).join() {
+            let delta = delta(timer);
+            for _ in 0..timer.tick(delta) {
+                events.single_write(TimerEvent {
+                    source: TimerSource::Entity(entity),
+                    event: timer.event.clone(),
+                });
+            }
+        }
+
+        for (id, timer) in &mut scheduler.timers {
+            let delta = delta(timer);
+            for _ in 0..timer.tick(delta) {
+                events.single_write(TimerEvent {
+                    source: TimerSource::Scheduler(*id),
+                    event: timer.event.clone(),
+                });
+            }
+        }
+        scheduler.timers.retain(|(_, timer)| !timer.finished);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use amethyst_core::{
+        ecs::{Builder, RunNow, World, WorldExt},
+        shrev::EventChannel,
+        timing::Time,
+    };
+
+    use super::{Timer, TimerEvent, TimerScheduler, TimerSource, TimerSystem};
+
+    #[test]
+    fn repeating_timer_fires_per_interval() {
+        let mut timer = Timer::repeating(Duration::from_millis(100), ());
+        assert_eq!(0, timer.tick(Duration::from_millis(50)));
+        assert_eq!(2, timer.tick(Duration::from_millis(200)));
+        assert_eq!(Duration::from_millis(50), timer.elapsed());
+
+        timer.pause();
+        assert_eq!(0, timer.tick(Duration::from_millis(200)));
+        timer.resume();
+        assert_eq!(1, timer.tick(Duration::from_millis(50)));
+        assert!(!timer.is_finished());
+    }
+
+    #[test]
+    fn system_fires_scaled_and_real_time_timers() {
+        let mut world = World::new();
+        let mut system = TimerSystem::<&'static str>::new();
+        system.setup(&mut world);
+        let mut reader = world
+            .fetch_mut::<EventChannel<TimerEvent<&'static str>>>()
+            .register_reader();
+
+        let entity = world
+            .create_entity()
+            .with(Timer::once(Duration::from_secs(1), "explode"))
+            .build();
+        let id = world
+            .fetch_mut::<TimerScheduler<&'static str>>()
+            .once(Duration::from_secs(1), "hint");
+        world
+            .fetch_mut::<TimerScheduler<&'static str>>()
+            .schedule(Timer::once(Duration::from_secs(1), "unscaled").real_time());
+        {
+            let mut time = world.fetch_mut::<Time>();
+            time.set_time_scale(2.0);
+            time.set_delta_seconds(0.5);
+        }
+        system.run_now(&world);
+
+        let events = world
+            .fetch::<EventChannel<TimerEvent<&'static str>>>()
+            .read(&mut reader)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                TimerEvent {
+                    source: TimerSource::Entity(entity),
+                    event: "explode",
+                },
+                TimerEvent {
+                    source: TimerSource::Scheduler(id),
+                    event: "hint",
+                },
+            ],
+            events
+        );
+        assert_eq!(1, world.fetch::<TimerScheduler<&'static str>>().len());
+        assert!(world
+            .read_storage::<Timer<&'static str>>()
+            .get(entity)
+            .unwrap()
+            .is_finished());
+    }
+}
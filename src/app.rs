@@ -1,6 +1,6 @@
 //! The core engine framework.
 
-use std::{env, marker::PhantomData, path::Path, sync::Arc, time::Duration};
+use std::{env, marker::PhantomData, num::NonZeroU32, path::Path, sync::Arc, time::Duration};
 
 use crate::shred::Resource;
 use derivative::Derivative;
@@ -19,7 +19,7 @@ use crate::{
     core::{
         frame_limiter::{FrameLimiter, FrameRateLimitConfig, FrameRateLimitStrategy},
         shrev::{EventChannel, ReaderId},
-        timing::{Stopwatch, Time},
+        timing::{FixedStepOverflow, Stopwatch, Time},
         ArcThreadPool, EventReader, Named,
     },
     ecs::prelude::{Component, Read, World, WorldExt, Write},
@@ -838,6 +838,25 @@ where
         self
     }
 
+    /// Limits the number of fixed updates run per frame, so the game can recover from long
+    /// frames. No limit is set by default.
+    ///
+    /// # Parameters
+    ///
+    /// `max_steps`: The maximum number of fixed updates per frame.
+    /// `overflow`: Whether the time of the fixed updates exceeding the limit is dropped or
+    /// caught up in later frames.
+    ///
+    /// # Returns
+    ///
+    /// This function returns the ApplicationBuilder after modifying it.
+    pub fn with_max_fixed_steps(self, max_steps: NonZeroU32, overflow: FixedStepOverflow) -> Self {
+        self.world
+            .write_resource::<Time>()
+            .set_max_fixed_steps(Some(max_steps), overflow);
+        self
+    }
+
     /// Tells the resulting application window to ignore close events if ignore is true.
     /// This will make your game window unresponsive to operating system close commands.
     /// Use with caution.